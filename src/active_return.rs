@@ -1,23 +1,53 @@
-use crate::{annualized_return::AnnualizedReturn, mode, Indicator};
+use crate::{
+    annualized_return::AnnualizedReturn,
+    history::{History, Values},
+    mode,
+    nan_policy::NanPolicy,
+    Error, Indicator,
+};
 
 #[derive(Debug)]
 pub struct ActiveReturn<T> {
     pub mode: T,
     pub freq: usize,
+    pub periods_per_year: f64,
     first_annualized_return: AnnualizedReturn<T>,
     second_annualized_return: AnnualizedReturn<T>,
-    pub values: Vec<Option<f64>>,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
 }
 
 impl<T: Clone> ActiveReturn<T> {
-    pub fn new(mode: T, freq: usize) -> Self {
-        ActiveReturn {
+    pub fn new(mode: T, freq: usize, periods_per_year: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        if !(periods_per_year.is_finite() && periods_per_year > 0.0) {
+            return Err(Error::InvalidParameter);
+        }
+        Ok(ActiveReturn {
             mode: mode.clone(),
             freq,
-            first_annualized_return: AnnualizedReturn::new(mode.clone(), freq),
-            second_annualized_return: AnnualizedReturn::new(mode, freq),
-            values: Vec::with_capacity(freq),
-        }
+            periods_per_year,
+            first_annualized_return: AnnualizedReturn::new(mode.clone(), freq, periods_per_year)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            second_annualized_return: AnnualizedReturn::new(mode, freq, periods_per_year)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
     }
 }
 
@@ -25,23 +55,41 @@ impl Indicator for ActiveReturn<mode::Geometric> {
     type Input = (f64, f64);
     type Output = f64;
 
-    fn feed(&mut self, (first_input, second_input): Self::Input) {
-        self.first_annualized_return.feed(first_input);
-        self.second_annualized_return.feed(second_input);
+    fn feed(&mut self, (first_input, second_input): Self::Input) -> Result<(), Error> {
+        let (Some(first_input), Some(second_input)) = (
+            self.nan_policy.apply(first_input)?,
+            self.nan_policy.apply(second_input)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.first_annualized_return.feed(first_input)?;
+        self.second_annualized_return.feed(second_input)?;
         if let (Some(v1), Some(v2)) = (
             self.first_annualized_return.last(),
             self.second_annualized_return.last(),
         ) {
             self.values.push(Some(v1 - v2));
-        };
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
     }
 
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
@@ -49,23 +97,83 @@ impl Indicator for ActiveReturn<mode::Simple> {
     type Input = (f64, f64);
     type Output = f64;
 
-    fn feed(&mut self, (first_input, second_input): Self::Input) {
-        self.first_annualized_return.feed(first_input);
-        self.second_annualized_return.feed(second_input);
+    fn feed(&mut self, (first_input, second_input): Self::Input) -> Result<(), Error> {
+        let (Some(first_input), Some(second_input)) = (
+            self.nan_policy.apply(first_input)?,
+            self.nan_policy.apply(second_input)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.first_annualized_return.feed(first_input)?;
+        self.second_annualized_return.feed(second_input)?;
         if let (Some(v1), Some(v2)) = (
             self.first_annualized_return.last(),
             self.second_annualized_return.last(),
         ) {
             self.values.push(Some(v1 - v2));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+impl Indicator for ActiveReturn<mode::Log> {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (first_input, second_input): Self::Input) -> Result<(), Error> {
+        let (Some(first_input), Some(second_input)) = (
+            self.nan_policy.apply(first_input)?,
+            self.nan_policy.apply(second_input)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
         };
+        self.first_annualized_return.feed(first_input)?;
+        self.second_annualized_return.feed(second_input)?;
+        if let (Some(v1), Some(v2)) = (
+            self.first_annualized_return.last(),
+            self.second_annualized_return.last(),
+        ) {
+            self.values.push(Some(v1 - v2));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
     }
 
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
@@ -83,10 +191,10 @@ mod test {
     ];
     #[test]
     fn geometric() {
-        let mut indicator = ActiveReturn::new(mode::Geometric, 10);
+        let mut indicator = ActiveReturn::new(mode::Geometric, 10, 10.0).unwrap();
         XS.iter()
             .zip(YS.iter())
-            .for_each(|(x, y)| indicator.feed((*x, *y)));
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
         assert_approx_eq!(
             f64,
             0.07183306403588108,
@@ -94,4 +202,21 @@ mod test {
             epsilon = 0.0000001
         );
     }
+    #[test]
+    fn log() {
+        let mut indicator = ActiveReturn::new(mode::Log, 10, 10.0).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((x.ln_1p(), y.ln_1p())).unwrap());
+        let (xs, ys): (f64, f64) = (
+            XS.iter().map(|x| x.ln_1p()).sum(),
+            YS.iter().map(|y| y.ln_1p()).sum(),
+        );
+        assert_approx_eq!(
+            f64,
+            xs - ys,
+            *indicator.last().unwrap(),
+            epsilon = 0.0000001
+        );
+    }
 }
@@ -1,54 +1,105 @@
-use crate::{mode, Indicator, ReturnExt};
-use statrs::{self, statistics::Statistics};
+use crate::{
+    feed_all,
+    history::{History, Values},
+    mode,
+    nan_policy::NanPolicy,
+    rolling::{RollingProduct, RollingSum, Window},
+    Error, Indicator, ReturnExt,
+};
 
 #[derive(Debug)]
 pub struct AnnualizedReturn<T> {
     pub mode: T,
     pub freq: usize,
-    input: Vec<f64>,
-    pub values: Vec<Option<f64>>,
+    pub periods_per_year: f64,
+    input: Window,
+    product: RollingProduct,
+    sum: RollingSum,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
 }
 
 impl<T> AnnualizedReturn<T> {
-    pub fn new(mode: T, freq: usize) -> Self {
-        AnnualizedReturn {
+    pub fn new(mode: T, freq: usize, periods_per_year: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        if !(periods_per_year.is_finite() && periods_per_year > 0.0) {
+            return Err(Error::InvalidParameter);
+        }
+        Ok(AnnualizedReturn {
             mode,
             freq,
-            input: Vec::with_capacity(freq),
-            values: Vec::with_capacity(freq),
-        }
+            periods_per_year,
+            input: Window::new(freq),
+            product: RollingProduct::default(),
+            sum: RollingSum::default(),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
     }
 }
 
 impl Indicator for AnnualizedReturn<mode::Geometric> {
     type Input = f64;
     type Output = f64;
-    fn feed(&mut self, ret: Self::Input) {
-        self.input.push(ret);
-        if self.input.len() >= self.freq {
-            let n = self.input.len().min(self.freq);
-            let ret = self.input[self.input.len() - self.freq..]
-                .iter()
-                .map(|x| x + 1.0)
-                .fold(1.0, |acc, x| acc * x);
-            let annret = ret.powf(self.freq as f64 / n as f64) - 1.0;
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        if let Some(old) = self.input.push(ret) {
+            self.product.remove(old);
+        }
+        self.product.add(ret);
+        if self.input.is_full() {
+            let n = self.input.len();
+            let annret = self
+                .product
+                .product()
+                .powf(self.periods_per_year / n as f64)
+                - 1.0;
             self.values.push(Some(annret));
         } else {
             self.values.push(None)
         }
+        Ok(())
     }
 
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
 pub trait AnnualizedReturnExt<T> {
-    fn annualized_return(&self, mode: T, freq: usize) -> Option<AnnualizedReturn<T>>;
+    fn annualized_return(
+        &self,
+        mode: T,
+        freq: usize,
+        periods_per_year: f64,
+    ) -> Result<AnnualizedReturn<T>, Error>;
 }
 
 impl<T> AnnualizedReturnExt<mode::Geometric> for T
@@ -59,35 +110,50 @@ where
         &self,
         mode: mode::Geometric,
         freq: usize,
-    ) -> Option<AnnualizedReturn<mode::Geometric>> {
-        let mut indicator = AnnualizedReturn::new(mode, freq);
-        if let Some(ret) = self.ret() {
-            ret.iter().for_each(|&v| indicator.feed(v));
-            Some(indicator)
-        } else {
-            None
-        }
+        periods_per_year: f64,
+    ) -> Result<AnnualizedReturn<mode::Geometric>, Error> {
+        feed_all(
+            AnnualizedReturn::new(mode, freq, periods_per_year)?,
+            self.ret()?,
+        )
     }
 }
 
 impl Indicator for AnnualizedReturn<mode::Simple> {
     type Input = f64;
     type Output = f64;
-    fn feed(&mut self, ret: Self::Input) {
-        self.input.push(ret);
-        if self.input.len() >= self.freq {
-            let mean = self.input[self.input.len() - self.freq..].iter().mean();
-            self.values.push(Some(mean * self.freq as f64));
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        if let Some(old) = self.input.push(ret) {
+            self.sum.remove(old);
+        }
+        self.sum.add(ret);
+        if self.input.is_full() {
+            let mean = self.sum.sum() / self.input.len() as f64;
+            self.values.push(Some(mean * self.periods_per_year));
         } else {
             self.values.push(None)
         }
+        Ok(())
     }
+
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
@@ -99,14 +165,67 @@ where
         &self,
         mode: mode::Simple,
         freq: usize,
-    ) -> Option<AnnualizedReturn<mode::Simple>> {
-        let mut indicator = AnnualizedReturn::new(mode, freq);
-        if let Some(ret) = self.ret() {
-            ret.iter().for_each(|&v| indicator.feed(v));
-            Some(indicator)
+        periods_per_year: f64,
+    ) -> Result<AnnualizedReturn<mode::Simple>, Error> {
+        feed_all(
+            AnnualizedReturn::new(mode, freq, periods_per_year)?,
+            self.ret()?,
+        )
+    }
+}
+
+impl Indicator for AnnualizedReturn<mode::Log> {
+    type Input = f64;
+    type Output = f64;
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        if let Some(old) = self.input.push(ret) {
+            self.sum.remove(old);
+        }
+        self.sum.add(ret);
+        if self.input.is_full() {
+            let mean = self.sum.sum() / self.input.len() as f64;
+            self.values.push(Some(mean * self.periods_per_year));
         } else {
-            None
+            self.values.push(None)
         }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+impl<T> AnnualizedReturnExt<mode::Log> for T
+where
+    T: ReturnExt,
+{
+    fn annualized_return(
+        &self,
+        mode: mode::Log,
+        freq: usize,
+        periods_per_year: f64,
+    ) -> Result<AnnualizedReturn<mode::Log>, Error> {
+        feed_all(
+            AnnualizedReturn::new(mode, freq, periods_per_year)?,
+            self.log_ret()?,
+        )
     }
 }
 
@@ -114,7 +233,7 @@ where
 mod test {
     use crate::{
         annualized_return::{mode, AnnualizedReturn},
-        Indicator,
+        Error, Indicator,
     };
     use float_cmp::assert_approx_eq;
 
@@ -123,8 +242,8 @@ mod test {
     ];
     #[test]
     fn geometric() {
-        let mut indicator = AnnualizedReturn::new(mode::Geometric, 10);
-        XS.iter().for_each(|x| indicator.feed(*x));
+        let mut indicator = AnnualizedReturn::new(mode::Geometric, 10, 10.0).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
         assert_approx_eq!(
             f64,
             0.19135615147149543,
@@ -134,8 +253,64 @@ mod test {
     }
     #[test]
     fn simple() {
-        let mut indicator = AnnualizedReturn::new(mode::Simple, 10);
-        XS.iter().for_each(|x| indicator.feed(*x));
+        let mut indicator = AnnualizedReturn::new(mode::Simple, 10, 10.0).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
         assert_approx_eq!(f64, 0.179, *indicator.last().unwrap(), epsilon = 0.0000001);
     }
+    #[test]
+    fn log() {
+        let mut indicator = AnnualizedReturn::new(mode::Log, 10, 10.0).unwrap();
+        XS.iter().for_each(|x| indicator.feed(x.ln_1p()).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.19135615147149543_f64.ln_1p(),
+            *indicator.last().unwrap(),
+            epsilon = 0.0000001
+        );
+    }
+    #[test]
+    fn rolling_matches_batch() {
+        let mut geometric = AnnualizedReturn::new(mode::Geometric, 4, 4.0).unwrap();
+        let mut simple = AnnualizedReturn::new(mode::Simple, 4, 4.0).unwrap();
+        XS.iter().for_each(|x| {
+            geometric.feed(*x).unwrap();
+            simple.feed(*x).unwrap();
+        });
+        for (i, (g, s)) in geometric.iter().zip(simple.iter()).enumerate().skip(3) {
+            let xs = &XS[i - 3..=i];
+            assert_approx_eq!(
+                f64,
+                xs.iter().map(|x| 1.0 + x).product::<f64>() - 1.0,
+                *g.unwrap(),
+                epsilon = 0.0000001
+            );
+            assert_approx_eq!(
+                f64,
+                xs.iter().sum::<f64>(),
+                *s.unwrap(),
+                epsilon = 0.0000001
+            );
+        }
+    }
+    #[test]
+    fn window_shorter_than_year() {
+        let mut indicator = AnnualizedReturn::new(mode::Geometric, 5, 252.0).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        let ret = XS[5..].iter().map(|x| 1.0 + x).product::<f64>();
+        assert_approx_eq!(
+            f64,
+            ret.powf(252.0 / 5.0) - 1.0,
+            *indicator.last().unwrap(),
+            epsilon = 0.0000001
+        );
+    }
+    #[test]
+    fn invalid_periods_per_year() {
+        for periods_per_year in [0.0, -252.0, f64::NAN, f64::INFINITY] {
+            assert_eq!(
+                Err(Error::InvalidParameter),
+                AnnualizedReturn::new(mode::Geometric, 10, periods_per_year).map(|_| ())
+            );
+        }
+    }
 }
@@ -1,21 +1,47 @@
-use statrs::statistics::Statistics;
-
-use crate::{Indicator, ReturnExt};
+use crate::{
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    rolling::{RollingMoments, Window},
+    Error, Indicator, ReturnExt,
+};
 
 #[derive(Debug)]
 pub struct AnnualizedRisk {
     pub freq: usize,
-    input: Vec<f64>,
-    pub values: Vec<Option<f64>>,
+    pub periods_per_year: f64,
+    input: Window,
+    moments: RollingMoments,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
 }
 
 impl AnnualizedRisk {
-    pub fn new(freq: usize) -> Self {
-        Self {
-            freq,
-            input: Vec::with_capacity(freq),
-            values: Vec::with_capacity(freq),
+    pub fn new(freq: usize, periods_per_year: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        if !(periods_per_year.is_finite() && periods_per_year > 0.0) {
+            return Err(Error::InvalidParameter);
         }
+        Ok(Self {
+            freq,
+            periods_per_year,
+            input: Window::new(freq),
+            moments: RollingMoments::default(),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
     }
 }
 
@@ -23,42 +49,51 @@ impl Indicator for AnnualizedRisk {
     type Input = f64;
     type Output = f64;
 
-    fn feed(&mut self, ret: Self::Input) {
-        self.input.push(ret);
-        if self.input.len() >= self.freq {
-            let value = self.input[self.input.len() - self.freq..].iter().std_dev()
-                * (self.freq as f64).sqrt();
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        if let Some(old) = self.input.push(ret) {
+            self.moments.remove(old);
+        }
+        self.moments.add(ret);
+        if self.input.is_full() {
+            let value = self.moments.std_dev() * self.periods_per_year.sqrt();
             self.values.push(Some(value));
         } else {
             self.values.push(None)
         }
+        Ok(())
     }
 
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
 pub trait AnnualizedRiskExt {
-    fn annualized_risk(&self, freq: usize) -> Option<AnnualizedRisk>;
+    fn annualized_risk(&self, freq: usize, periods_per_year: f64) -> Result<AnnualizedRisk, Error>;
 }
 
 impl<T> AnnualizedRiskExt for T
 where
     T: ReturnExt,
 {
-    fn annualized_risk(&self, freq: usize) -> Option<AnnualizedRisk> {
-        let mut indicator = AnnualizedRisk::new(freq);
-        if let Some(ret) = self.ret() {
-            ret.iter().for_each(|&v| indicator.feed(v));
-            Some(indicator)
-        } else {
-            None
-        }
+    fn annualized_risk(&self, freq: usize, periods_per_year: f64) -> Result<AnnualizedRisk, Error> {
+        feed_all(AnnualizedRisk::new(freq, periods_per_year)?, self.ret()?)
     }
 }
 
@@ -66,14 +101,15 @@ where
 mod test {
     use crate::{annualized_risk::AnnualizedRisk, Indicator};
     use float_cmp::assert_approx_eq;
+    use statrs::statistics::Statistics;
 
     static XS: [f64; 10] = [
         0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
     ];
     #[test]
     fn annualized_risk() {
-        let mut indicator = AnnualizedRisk::new(10);
-        XS.iter().for_each(|x| indicator.feed(*x));
+        let mut indicator = AnnualizedRisk::new(10, 10.0).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
         assert_approx_eq!(
             f64,
             0.07346125206907078,
@@ -81,4 +117,15 @@ mod test {
             epsilon = 0.0000001
         );
     }
+    #[test]
+    fn window_shorter_than_year() {
+        let mut indicator = AnnualizedRisk::new(5, 252.0).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        assert_approx_eq!(
+            f64,
+            XS[5..].iter().std_dev() * 252.0_f64.sqrt(),
+            *indicator.last().unwrap(),
+            epsilon = 0.0000001
+        );
+    }
 }
@@ -0,0 +1,157 @@
+use erfurt::candle::CandlesExt;
+
+use crate::{
+    aligned_ret, feed_all,
+    history::{History, Values},
+    jensens_alpha::JensensAlpha,
+    nan_policy::NanPolicy,
+    specific_risk::SpecificRisk,
+    Error, Indicator,
+};
+
+/// [`JensensAlpha`] per unit of [`SpecificRisk`], fed as
+/// `(portfolio, benchmark)`. `risk_free` is an annual rate.
+#[derive(Debug)]
+pub struct AppraisalRatio {
+    pub freq: usize,
+    pub periods_per_year: f64,
+    pub risk_free: f64,
+    jensens_alpha: JensensAlpha,
+    specific_risk: SpecificRisk,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl AppraisalRatio {
+    pub fn new(freq: usize, periods_per_year: f64, risk_free: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            periods_per_year,
+            risk_free,
+            jensens_alpha: JensensAlpha::new(freq, periods_per_year, risk_free)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            specific_risk: SpecificRisk::new(freq, periods_per_year)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for AppraisalRatio {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        let alpha = self.jensens_alpha.feed((portfolio, benchmark));
+        let risk = self.specific_risk.feed((portfolio, benchmark));
+        if let Err(err) = alpha.and(risk) {
+            self.values.push(None);
+            return Err(err);
+        }
+        match (self.jensens_alpha.last(), self.specific_risk.last()) {
+            (Some(_), Some(&0.0)) => {
+                self.values.push(None);
+                return Err(Error::ZeroVariance);
+            }
+            (Some(alpha), Some(specific_risk)) => self.values.push(Some(alpha / specific_risk)),
+            _ => self.values.push(None),
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait AppraisalRatioExt {
+    fn appraisal_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: f64,
+    ) -> Result<AppraisalRatio, Error>;
+}
+
+impl<T> AppraisalRatioExt for T
+where
+    T: CandlesExt,
+{
+    fn appraisal_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: f64,
+    ) -> Result<AppraisalRatio, Error> {
+        feed_all(
+            AppraisalRatio::new(freq, periods_per_year, risk_free)?,
+            aligned_ret(self, benchmark)?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::AppraisalRatio;
+    use crate::Indicator;
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+    static YS: [f64; 10] = [
+        -0.005, 0.081, 0.04, -0.037, -0.061, 0.058, -0.049, -0.021, 0.062, 0.058,
+    ];
+
+    #[test]
+    fn appraisal_ratio() {
+        let mut indicator = AppraisalRatio::new(10, 10.0, 0.02).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(
+            f64,
+            2.304502148657299,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+}
@@ -1,21 +1,44 @@
 use statrs::statistics::Statistics;
 
-use crate::{continuous_drawdown::ContinousDrawdown, Indicator, ReturnExt};
+use crate::{
+    continuous_drawdown::ContinousDrawdown,
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    Error, Indicator, ReturnExt,
+};
 
 #[derive(Debug)]
 pub struct AverageDrawdown {
     pub freq: usize,
     continuous_drawdown: ContinousDrawdown,
-    pub values: Vec<Option<f64>>,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
 }
 
 impl AverageDrawdown {
-    pub fn new(freq: usize) -> Self {
-        Self {
-            freq,
-            continuous_drawdown: ContinousDrawdown::new(freq),
-            values: Vec::with_capacity(freq),
+    pub fn new(freq: usize) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
         }
+        Ok(Self {
+            freq,
+            continuous_drawdown: ContinousDrawdown::new(freq)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
     }
 }
 
@@ -23,41 +46,48 @@ impl Indicator for AverageDrawdown {
     type Input = f64;
     type Output = f64;
 
-    fn feed(&mut self, ret: Self::Input) {
-        self.continuous_drawdown.feed(ret);
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.continuous_drawdown.feed(ret)?;
         if let Some(xs) = &self.continuous_drawdown.last() {
             let value = xs.iter().mean();
             self.values.push(Some(value));
         } else {
             self.values.push(None)
         }
+        Ok(())
     }
 
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
 pub trait AverageDrawdownExt {
-    fn average_drawdown(&self, freq: usize) -> Option<AverageDrawdown>;
+    fn average_drawdown(&self, freq: usize) -> Result<AverageDrawdown, Error>;
 }
 
 impl<T> AverageDrawdownExt for T
 where
     T: ReturnExt,
 {
-    fn average_drawdown(&self, freq: usize) -> Option<AverageDrawdown> {
-        let mut indicator = AverageDrawdown::new(freq);
-        if let Some(ret) = self.ret() {
-            ret.iter().for_each(|&v| indicator.feed(v));
-            Some(indicator)
-        } else {
-            None
-        }
+    fn average_drawdown(&self, freq: usize) -> Result<AverageDrawdown, Error> {
+        feed_all(AverageDrawdown::new(freq)?, self.ret()?)
     }
 }
 
@@ -73,8 +103,8 @@ mod test {
 
     #[test]
     fn average_drawdown() {
-        let mut indicator = AverageDrawdown::new(10);
-        XS.iter().for_each(|x| indicator.feed(*x));
+        let mut indicator = AverageDrawdown::new(10).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
         dbg!(indicator.last().unwrap());
         assert_approx_eq!(f64, 0.0115, *indicator.last().unwrap(), epsilon = 0.0000001)
     }
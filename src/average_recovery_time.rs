@@ -0,0 +1,122 @@
+use crate::{
+    drawdown_episode::drawdown_episodes,
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    rolling::Window,
+    Error, Indicator, ReturnExt,
+};
+
+/// Mean number of periods from trough to recovery over the recovered
+/// drawdowns in the window, `None` if none has recovered. Each feed rebuilds
+/// the window's drawdown episodes, so an update costs `O(freq)`.
+#[derive(Debug)]
+pub struct AverageRecoveryTime {
+    pub freq: usize,
+    input: Window,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl AverageRecoveryTime {
+    pub fn new(freq: usize) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            input: Window::new(freq),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for AverageRecoveryTime {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.input.push(ret);
+        if self.input.is_full() && !self.input.is_finite() {
+            self.values.push(Some(f64::NAN));
+        } else if self.input.is_full() {
+            let xs: Vec<f64> = self.input.iter().copied().collect();
+            let recoveries: Vec<usize> = drawdown_episodes(&xs)?
+                .iter()
+                .filter_map(|episode| episode.recovery_length)
+                .collect();
+            if recoveries.is_empty() {
+                self.values.push(None);
+            } else {
+                let value = recoveries.iter().sum::<usize>() as f64 / recoveries.len() as f64;
+                self.values.push(Some(value));
+            }
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait AverageRecoveryTimeExt {
+    fn average_recovery_time(&self, freq: usize) -> Result<AverageRecoveryTime, Error>;
+}
+
+impl<T> AverageRecoveryTimeExt for T
+where
+    T: ReturnExt,
+{
+    fn average_recovery_time(&self, freq: usize) -> Result<AverageRecoveryTime, Error> {
+        feed_all(AverageRecoveryTime::new(freq)?, self.ret()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AverageRecoveryTime;
+    use crate::Indicator;
+
+    #[test]
+    fn average_recovery_time() {
+        let mut indicator = AverageRecoveryTime::new(4).unwrap();
+        [0.01, -0.02, -0.01, 0.02, 0.02, -0.01, 0.02]
+            .iter()
+            .for_each(|x| indicator.feed(*x).unwrap());
+        let values: Vec<_> = indicator.iter().map(|x| x.copied()).collect();
+        assert_eq!(
+            vec![None, None, None, None, Some(2.0), Some(1.0), Some(1.0)],
+            values
+        );
+    }
+}
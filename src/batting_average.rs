@@ -0,0 +1,166 @@
+use erfurt::candle::CandlesExt;
+
+use crate::{
+    aligned_ret, feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    paired::{HitRate, PairedWindow},
+    Error, Indicator,
+};
+
+/// Share of periods in which the portfolio beat the benchmark, fed as
+/// `(portfolio, benchmark)`.
+#[derive(Debug)]
+pub struct BattingAverage {
+    pub freq: usize,
+    window: PairedWindow,
+    hit_rate: HitRate,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl BattingAverage {
+    pub fn new(freq: usize) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            window: PairedWindow::new(freq),
+            hit_rate: HitRate::new(|_| true, |p, b| p > b),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for BattingAverage {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.window.feed(
+            &mut self.hit_rate,
+            &mut self.values,
+            (portfolio, benchmark),
+            HitRate::rate,
+        )
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait BattingAverageExt {
+    fn batting_average<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+    ) -> Result<BattingAverage, Error>;
+}
+
+impl<T> BattingAverageExt for T
+where
+    T: CandlesExt,
+{
+    fn batting_average<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+    ) -> Result<BattingAverage, Error> {
+        feed_all(BattingAverage::new(freq)?, aligned_ret(self, benchmark)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::BattingAverage;
+    use crate::{nan_policy::NanPolicy, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+    static YS: [f64; 10] = [
+        -0.005, 0.081, 0.04, -0.037, -0.061, 0.058, -0.049, -0.021, 0.062, 0.058,
+    ];
+
+    #[test]
+    fn batting_average() {
+        let mut indicator = BattingAverage::new(10).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(f64, 0.5, *indicator.last().unwrap(), epsilon = 1e-9);
+    }
+
+    #[test]
+    fn rolling() {
+        let mut indicator = BattingAverage::new(3).unwrap();
+        for x in [
+            (0.02, 0.01),
+            (-0.01, 0.02),
+            (0.01, -0.02),
+            (-0.03, -0.01),
+            (0.03, 0.02),
+        ] {
+            indicator.feed(x).unwrap();
+        }
+        let values: Vec<_> = indicator.iter().map(|x| x.copied()).collect();
+        assert_eq!(
+            vec![
+                None,
+                None,
+                Some(2.0 / 3.0),
+                Some(1.0 / 3.0),
+                Some(2.0 / 3.0)
+            ],
+            values
+        );
+    }
+
+    #[test]
+    fn propagates_nan() {
+        let mut indicator = BattingAverage::new(2)
+            .unwrap()
+            .with_nan_policy(NanPolicy::Propagate);
+        [(0.01, 0.0), (f64::NAN, 0.0), (0.02, 0.01), (0.03, 0.01)]
+            .iter()
+            .for_each(|x| indicator.feed(*x).unwrap());
+        let values: Vec<_> = indicator.iter().collect();
+        assert!(values[1].unwrap().is_nan());
+        assert!(values[2].unwrap().is_nan());
+        assert_eq!(Some(&1.0), values[3]);
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{
+    annualized_return::AnnualizedReturn,
+    continuous_drawdown::ContinousDrawdown,
+    feed_all,
+    history::{History, Values},
+    mode,
+    nan_policy::NanPolicy,
+    Error, Indicator, ReturnExt,
+};
+
+#[derive(Debug)]
+pub struct BurkeRatio {
+    pub freq: usize,
+    pub periods_per_year: f64,
+    annualized_return: AnnualizedReturn<mode::Geometric>,
+    continuous_drawdown: ContinousDrawdown,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl BurkeRatio {
+    pub fn new(freq: usize, periods_per_year: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            periods_per_year,
+            annualized_return: AnnualizedReturn::new(mode::Geometric, freq, periods_per_year)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            continuous_drawdown: ContinousDrawdown::new(freq)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for BurkeRatio {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.annualized_return.feed(ret)?;
+        self.continuous_drawdown.feed(ret)?;
+        if let (Some(annret), Some(xs)) = (
+            self.annualized_return.last(),
+            self.continuous_drawdown.last(),
+        ) {
+            let drawdown = xs.iter().map(|x| x.powi(2)).sum::<f64>().sqrt();
+            if drawdown == 0.0 {
+                self.values.push(None);
+                return Err(Error::ZeroVariance);
+            }
+            self.values.push(Some(annret / drawdown));
+        } else {
+            self.values.push(None)
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait BurkeRatioExt {
+    fn burke_ratio(&self, freq: usize, periods_per_year: f64) -> Result<BurkeRatio, Error>;
+}
+
+impl<T> BurkeRatioExt for T
+where
+    T: ReturnExt,
+{
+    fn burke_ratio(&self, freq: usize, periods_per_year: f64) -> Result<BurkeRatio, Error> {
+        feed_all(BurkeRatio::new(freq, periods_per_year)?, self.ret()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::BurkeRatio;
+    use crate::{Error, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn burke_ratio() {
+        let mut indicator = BurkeRatio::new(10, 10.0).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.19135615147149543 / (0.009_f64.powi(2) + 0.014_f64.powi(2)).sqrt(),
+            *indicator.last().unwrap(),
+            epsilon = 0.0000001
+        );
+    }
+
+    #[test]
+    fn no_drawdown() {
+        let mut indicator = BurkeRatio::new(2, 10.0).unwrap();
+        indicator.feed(0.01).unwrap();
+        assert_eq!(Err(Error::ZeroVariance), indicator.feed(0.02));
+        assert_eq!(None, indicator.last());
+    }
+}
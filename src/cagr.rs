@@ -1,66 +1,157 @@
-use crate::{ror::RoR, Indicator, ReturnExt};
+use crate::{
+    feed_all,
+    history::{History, Values},
+    mode,
+    nan_policy::NanPolicy,
+    ror::RoR,
+    Error, Indicator, ReturnExt,
+};
 
+/// Compound annual growth rate of [`RoR`] over the window. The oldest input
+/// only sets the starting level, so the window spans `freq - 1` periods and is
+/// annualized over them; `freq` must therefore be at least 2.
 #[derive(Debug)]
-pub struct CAGR {
+pub struct CAGR<T> {
+    pub mode: T,
     pub freq: usize,
-    pub p: f64,
-    input: Vec<f64>,
-    ror: RoR,
-    pub values: Vec<Option<f64>>,
+    pub periods_per_year: f64,
+    ror: RoR<T>,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
 }
 
-impl CAGR {
-    pub fn new(freq: usize, p: f64) -> Self {
-        CAGR {
+impl<T: Clone> CAGR<T> {
+    pub fn new(mode: T, freq: usize, periods_per_year: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        if freq == 1 || !(periods_per_year.is_finite() && periods_per_year > 0.0) {
+            return Err(Error::InvalidParameter);
+        }
+        Ok(CAGR {
+            mode: mode.clone(),
             freq,
-            p,
-            input: Vec::with_capacity(freq),
-            ror: RoR::new(freq),
-            values: Vec::with_capacity(freq),
+            periods_per_year,
+            ror: RoR::new(mode, freq)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for CAGR<mode::Geometric> {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.ror.feed(ret)?;
+        if let Some(ror) = self.ror.last() {
+            let value = (1.0 + ror).powf(self.periods_per_year / (self.freq - 1) as f64) - 1.0;
+            self.values.push(Some(value));
+        } else {
+            self.values.push(None)
         }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
-impl Indicator for CAGR {
+impl Indicator for CAGR<mode::Log> {
     type Input = f64;
     type Output = f64;
 
-    fn feed(&mut self, ret: Self::Input) {
-        self.input.push(ret);
-        self.ror.feed(ret);
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.ror.feed(ret)?;
         if let Some(ror) = self.ror.last() {
-            let value = (1.0 + ror).powf(self.p) - 1.0;
+            let value = ror * self.periods_per_year / (self.freq - 1) as f64;
             self.values.push(Some(value));
         } else {
             self.values.push(None)
         }
+        Ok(())
     }
 
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
-pub trait CagrExt {
-    fn cagr(&self, freq: usize, p: f64) -> Option<CAGR>;
+pub trait CagrExt<T> {
+    fn cagr(&self, mode: T, freq: usize, periods_per_year: f64) -> Result<CAGR<T>, Error>;
 }
 
-impl<T> CagrExt for T
+impl<T> CagrExt<mode::Geometric> for T
 where
     T: ReturnExt,
 {
-    fn cagr(&self, freq: usize, p: f64) -> Option<CAGR> {
-        let mut indicator = CAGR::new(freq, p);
-        if let Some(ret) = self.ret() {
-            ret.iter().for_each(|&v| indicator.feed(v));
-            Some(indicator)
-        } else {
-            None
-        }
+    fn cagr(
+        &self,
+        mode: mode::Geometric,
+        freq: usize,
+        periods_per_year: f64,
+    ) -> Result<CAGR<mode::Geometric>, Error> {
+        feed_all(CAGR::new(mode, freq, periods_per_year)?, self.ret()?)
+    }
+}
+
+impl<T> CagrExt<mode::Log> for T
+where
+    T: ReturnExt,
+{
+    fn cagr(
+        &self,
+        mode: mode::Log,
+        freq: usize,
+        periods_per_year: f64,
+    ) -> Result<CAGR<mode::Log>, Error> {
+        feed_all(CAGR::new(mode, freq, periods_per_year)?, self.log_ret()?)
     }
 }
 
@@ -68,20 +159,56 @@ where
 mod test {
     use float_cmp::assert_approx_eq;
 
-    use crate::{cagr::CAGR, Indicator};
+    use crate::{cagr::CAGR, mode, Error, Indicator};
 
     static XS: [f64; 10] = [
         0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
     ];
     #[test]
     fn cagr() {
-        let mut indicator = CAGR::new(10, 12.0 / 10.0);
-        XS.iter().for_each(|&x| indicator.feed(x));
+        let mut indicator = CAGR::new(mode::Geometric, 10, 12.0).unwrap();
+        XS.iter().for_each(|&x| indicator.feed(x).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.257924,
+            *indicator.last().unwrap(),
+            epsilon = 0.000001
+        );
+    }
+    #[test]
+    fn log() {
+        let mut indicator = CAGR::new(mode::Log, 10, 12.0).unwrap();
+        XS.iter().for_each(|&x| indicator.feed(x.ln_1p()).unwrap());
         assert_approx_eq!(
             f64,
-            0.229388,
+            0.257924_f64.ln_1p(),
             *indicator.last().unwrap(),
             epsilon = 0.000001
         );
     }
+    #[test]
+    fn zero_periods_per_year() {
+        assert_eq!(
+            Err(Error::InvalidParameter),
+            CAGR::new(mode::Geometric, 10, 0.0).map(|_| ())
+        );
+    }
+    #[test]
+    fn constant_growth() {
+        let mut indicator = CAGR::new(mode::Geometric, 4, 4.0).unwrap();
+        (0..4).for_each(|_| indicator.feed(0.01).unwrap());
+        assert_approx_eq!(
+            f64,
+            1.01_f64.powi(4) - 1.0,
+            *indicator.last().unwrap(),
+            epsilon = 1e-12
+        );
+    }
+    #[test]
+    fn single_period() {
+        assert_eq!(
+            Err(Error::InvalidParameter),
+            CAGR::new(mode::Geometric, 1, 12.0).map(|_| ())
+        );
+    }
 }
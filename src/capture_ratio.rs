@@ -0,0 +1,233 @@
+use erfurt::candle::CandlesExt;
+
+use crate::{
+    aligned_ret,
+    down_capture_ratio::DownCaptureRatio,
+    feed_all,
+    history::{History, Values},
+    mode,
+    nan_policy::NanPolicy,
+    up_capture_ratio::UpCaptureRatio,
+    Error, Indicator,
+};
+
+/// [`UpCaptureRatio`] over [`DownCaptureRatio`], fed as
+/// `(portfolio, benchmark)`. A window where either is undefined, or the down
+/// capture is zero, is rejected with [`Error::Undefined`].
+#[derive(Debug)]
+pub struct CaptureRatio<T> {
+    pub mode: T,
+    pub freq: usize,
+    up_capture_ratio: UpCaptureRatio<T>,
+    down_capture_ratio: DownCaptureRatio<T>,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl<T: Clone> CaptureRatio<T> {
+    pub fn new(mode: T, freq: usize) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            mode: mode.clone(),
+            freq,
+            up_capture_ratio: UpCaptureRatio::new(mode.clone(), freq)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            down_capture_ratio: DownCaptureRatio::new(mode, freq)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for CaptureRatio<mode::Geometric> {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        let up = self.up_capture_ratio.feed((portfolio, benchmark));
+        let down = self.down_capture_ratio.feed((portfolio, benchmark));
+        if let Err(err) = up.and(down) {
+            self.values.push(None);
+            return Err(err);
+        }
+        match (self.up_capture_ratio.last(), self.down_capture_ratio.last()) {
+            (Some(_), Some(&0.0)) => {
+                self.values.push(None);
+                return Err(Error::Undefined);
+            }
+            (Some(up), Some(down)) => self.values.push(Some(up / down)),
+            _ => self.values.push(None),
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+impl Indicator for CaptureRatio<mode::Simple> {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        let up = self.up_capture_ratio.feed((portfolio, benchmark));
+        let down = self.down_capture_ratio.feed((portfolio, benchmark));
+        if let Err(err) = up.and(down) {
+            self.values.push(None);
+            return Err(err);
+        }
+        match (self.up_capture_ratio.last(), self.down_capture_ratio.last()) {
+            (Some(_), Some(&0.0)) => {
+                self.values.push(None);
+                return Err(Error::Undefined);
+            }
+            (Some(up), Some(down)) => self.values.push(Some(up / down)),
+            _ => self.values.push(None),
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait CaptureRatioExt<T> {
+    fn capture_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        mode: T,
+        freq: usize,
+    ) -> Result<CaptureRatio<T>, Error>;
+}
+
+impl<T> CaptureRatioExt<mode::Geometric> for T
+where
+    T: CandlesExt,
+{
+    fn capture_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        mode: mode::Geometric,
+        freq: usize,
+    ) -> Result<CaptureRatio<mode::Geometric>, Error> {
+        feed_all(
+            CaptureRatio::new(mode, freq)?,
+            aligned_ret(self, benchmark)?,
+        )
+    }
+}
+
+impl<T> CaptureRatioExt<mode::Simple> for T
+where
+    T: CandlesExt,
+{
+    fn capture_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        mode: mode::Simple,
+        freq: usize,
+    ) -> Result<CaptureRatio<mode::Simple>, Error> {
+        feed_all(
+            CaptureRatio::new(mode, freq)?,
+            aligned_ret(self, benchmark)?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::CaptureRatio;
+    use crate::{mode, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+    static YS: [f64; 10] = [
+        -0.005, 0.081, 0.04, -0.037, -0.061, 0.058, -0.049, -0.021, 0.062, 0.058,
+    ];
+
+    #[test]
+    fn simple() {
+        let mut indicator = CaptureRatio::new(mode::Simple, 10).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(
+            f64,
+            -0.5850963887114351,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn geometric() {
+        let mut indicator = CaptureRatio::new(mode::Geometric, 10).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(
+            f64,
+            -0.594815141953891,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+}
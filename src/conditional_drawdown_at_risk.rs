@@ -0,0 +1,149 @@
+use crate::{
+    continuous_drawdown::ContinousDrawdown,
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    value_at_risk::quantile,
+    Error, Indicator, ReturnExt,
+};
+
+/// Mean of the drawdowns in the window at or beyond their `alpha` quantile,
+/// the worst `1 - alpha` of them. Zero when there are no drawdowns.
+#[derive(Debug)]
+pub struct ConditionalDrawdownAtRisk {
+    pub freq: usize,
+    pub alpha: f64,
+    continuous_drawdown: ContinousDrawdown,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl ConditionalDrawdownAtRisk {
+    pub fn new(freq: usize, alpha: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        if !(alpha > 0.0 && alpha < 1.0) {
+            return Err(Error::InvalidParameter);
+        }
+        Ok(Self {
+            freq,
+            alpha,
+            continuous_drawdown: ContinousDrawdown::new(freq)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for ConditionalDrawdownAtRisk {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.continuous_drawdown.feed(ret)?;
+        if let Some(xs) = self.continuous_drawdown.last() {
+            let value = if xs.is_empty() {
+                0.0
+            } else {
+                let mut xs = xs.clone();
+                let dar = quantile(&mut xs, self.alpha);
+                let tail: Vec<f64> = xs.into_iter().skip_while(|&x| x < dar).collect();
+                tail.iter().sum::<f64>() / tail.len() as f64
+            };
+            self.values.push(Some(value));
+        } else {
+            self.values.push(None)
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait ConditionalDrawdownAtRiskExt {
+    fn conditional_drawdown_at_risk(
+        &self,
+        freq: usize,
+        alpha: f64,
+    ) -> Result<ConditionalDrawdownAtRisk, Error>;
+}
+
+impl<T> ConditionalDrawdownAtRiskExt for T
+where
+    T: ReturnExt,
+{
+    fn conditional_drawdown_at_risk(
+        &self,
+        freq: usize,
+        alpha: f64,
+    ) -> Result<ConditionalDrawdownAtRisk, Error> {
+        feed_all(ConditionalDrawdownAtRisk::new(freq, alpha)?, self.ret()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::ConditionalDrawdownAtRisk;
+    use crate::{nan_policy::NanPolicy, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn conditional_drawdown_at_risk() {
+        let mut indicator = ConditionalDrawdownAtRisk::new(10, 0.5).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        assert_approx_eq!(f64, 0.014, *indicator.last().unwrap(), epsilon = 1e-9);
+        let mut indicator = ConditionalDrawdownAtRisk::new(8, 0.5).unwrap();
+        [-0.01, 0.1, -0.02, 0.1, -0.03, 0.1, -0.04, 0.1]
+            .iter()
+            .for_each(|x| indicator.feed(*x).unwrap());
+        assert_approx_eq!(f64, 0.035, *indicator.last().unwrap(), epsilon = 1e-9);
+    }
+
+    #[test]
+    fn no_drawdown() {
+        let mut indicator = ConditionalDrawdownAtRisk::new(2, 0.95)
+            .unwrap()
+            .with_nan_policy(NanPolicy::Propagate);
+        [0.01, 0.02, f64::NAN]
+            .iter()
+            .for_each(|x| indicator.feed(*x).unwrap());
+        assert_eq!(Some(&0.0), indicator.get(1));
+        assert!(indicator.last().unwrap().is_nan());
+    }
+}
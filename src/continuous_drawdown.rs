@@ -1,19 +1,40 @@
-use crate::{Indicator, ReturnExt};
+use crate::{
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    rolling::Window,
+    Error, Indicator, ReturnExt,
+};
 
 #[derive(Debug)]
 pub struct ContinousDrawdown {
     pub freq: usize,
-    input: Vec<f64>,
-    pub values: Vec<Option<Vec<f64>>>,
+    input: Window,
+    pub nan_policy: NanPolicy,
+    pub values: Values<Vec<f64>>,
 }
 
 impl ContinousDrawdown {
-    pub fn new(freq: usize) -> Self {
-        Self {
-            freq,
-            input: Vec::with_capacity(freq),
-            values: Vec::with_capacity(freq),
+    pub fn new(freq: usize) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
         }
+        Ok(Self {
+            freq,
+            input: Window::new(freq),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
     }
 }
 
@@ -21,15 +42,18 @@ impl Indicator for ContinousDrawdown {
     type Input = f64;
     type Output = Vec<f64>;
 
-    fn feed(&mut self, ret: Self::Input) {
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
         self.input.push(ret);
-        if self.input.len() >= self.freq {
+        if self.input.is_full() && !self.input.is_finite() {
+            self.values.push(Some(vec![f64::NAN]));
+        } else if self.input.is_full() {
             let mut xs = Vec::with_capacity(self.freq);
             let mut s = 1.0;
-            for (i, &x) in self.input[self.input.len() - self.freq..]
-                .iter()
-                .enumerate()
-            {
+            for (i, &x) in self.input.iter().enumerate() {
                 if i == 0 && x < 0.0 {
                     s = x + 1.0;
                     continue;
@@ -57,33 +81,36 @@ impl Indicator for ContinousDrawdown {
         } else {
             self.values.push(None)
         }
+        Ok(())
     }
 
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
 pub trait ContinousDrawdownExt {
-    fn continuous_drawdown(&self, freq: usize) -> Option<ContinousDrawdown>;
+    fn continuous_drawdown(&self, freq: usize) -> Result<ContinousDrawdown, Error>;
 }
 
 impl<T> ContinousDrawdownExt for T
 where
     T: ReturnExt,
 {
-    fn continuous_drawdown(&self, freq: usize) -> Option<ContinousDrawdown> {
-        let mut indicator = ContinousDrawdown::new(freq);
-        if let Some(ret) = self.ret() {
-            ret.iter().for_each(|&v| indicator.feed(v));
-            Some(indicator)
-        } else {
-            None
-        }
+    fn continuous_drawdown(&self, freq: usize) -> Result<ContinousDrawdown, Error> {
+        feed_all(ContinousDrawdown::new(freq)?, self.ret()?)
     }
 }
 
@@ -100,8 +127,8 @@ mod test {
 
     #[test]
     fn drawdown() {
-        let mut indicator = ContinousDrawdown::new(10);
-        XS.iter().for_each(|x| indicator.feed(*x));
+        let mut indicator = ContinousDrawdown::new(10).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
         let valid = [0.009, 0.014];
         valid
             .iter()
@@ -0,0 +1,155 @@
+use erfurt::candle::CandlesExt;
+
+use crate::{
+    aligned_ret, feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    rolling::{RollingCovariance, Window},
+    Error, Indicator,
+};
+
+/// Pearson correlation of portfolio and benchmark returns, fed as
+/// `(portfolio, benchmark)`.
+#[derive(Debug)]
+pub struct Correlation {
+    pub freq: usize,
+    portfolio: Window,
+    benchmark: Window,
+    covariance: RollingCovariance,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl Correlation {
+    pub fn new(freq: usize) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            portfolio: Window::new(freq),
+            benchmark: Window::new(freq),
+            covariance: RollingCovariance::default(),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for Correlation {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        if let (Some(old), Some(old_benchmark)) = (
+            self.portfolio.push(portfolio),
+            self.benchmark.push(benchmark),
+        ) {
+            self.covariance.remove(old, old_benchmark);
+        }
+        self.covariance.add(portfolio, benchmark);
+        if self.portfolio.is_full() {
+            let variance = self.covariance.variance_x() * self.covariance.variance_y();
+            if variance == 0.0 {
+                self.values.push(None);
+                return Err(Error::ZeroVariance);
+            }
+            self.values
+                .push(Some(self.covariance.covariance() / variance.sqrt()));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait CorrelationExt {
+    fn correlation<B: CandlesExt>(&self, benchmark: &B, freq: usize) -> Result<Correlation, Error>;
+}
+
+impl<T> CorrelationExt for T
+where
+    T: CandlesExt,
+{
+    fn correlation<B: CandlesExt>(&self, benchmark: &B, freq: usize) -> Result<Correlation, Error> {
+        feed_all(Correlation::new(freq)?, aligned_ret(self, benchmark)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::Correlation;
+    use crate::{Error, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+    static YS: [f64; 10] = [
+        -0.005, 0.081, 0.04, -0.037, -0.061, 0.058, -0.049, -0.021, 0.062, 0.058,
+    ];
+
+    #[test]
+    fn correlation() {
+        let mut indicator = Correlation::new(10).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.05249176466360621,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn rolls_into_flat_window() {
+        let mut indicator = Correlation::new(3).unwrap();
+        for x in [
+            (0.037, 0.02),
+            (-0.021, 0.01),
+            (0.013, -0.03),
+            (0.01, 0.04),
+            (0.01, 0.01),
+        ] {
+            indicator.feed(x).unwrap();
+        }
+        assert_eq!(Err(Error::ZeroVariance), indicator.feed((0.01, 0.03)));
+        assert_eq!(None, indicator.last());
+    }
+}
@@ -0,0 +1,233 @@
+use erfurt::candle::CandlesExt;
+
+use crate::{
+    aligned_ret, feed_all,
+    history::{History, Values},
+    mode,
+    nan_policy::NanPolicy,
+    paired::{Capture, PairedWindow},
+    Error, Indicator,
+};
+
+/// Portfolio return over benchmark return across the periods in which the
+/// benchmark fell, fed as `(portfolio, benchmark)`. `mode::Simple` compares
+/// arithmetic means, `mode::Geometric` compounded mean returns. A window
+/// without such a period, or whose compounded mean is undefined, is rejected
+/// with [`Error::Undefined`].
+#[derive(Debug)]
+pub struct DownCaptureRatio<T> {
+    pub mode: T,
+    pub freq: usize,
+    window: PairedWindow,
+    capture: Capture,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl<T> DownCaptureRatio<T> {
+    pub fn new(mode: T, freq: usize) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            mode,
+            freq,
+            window: PairedWindow::new(freq),
+            capture: Capture::new(|b| b < 0.0),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for DownCaptureRatio<mode::Geometric> {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.window.feed(
+            &mut self.capture,
+            &mut self.values,
+            (portfolio, benchmark),
+            Capture::geometric,
+        )
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+impl Indicator for DownCaptureRatio<mode::Simple> {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.window.feed(
+            &mut self.capture,
+            &mut self.values,
+            (portfolio, benchmark),
+            Capture::simple,
+        )
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait DownCaptureRatioExt<T> {
+    fn down_capture_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        mode: T,
+        freq: usize,
+    ) -> Result<DownCaptureRatio<T>, Error>;
+}
+
+impl<T> DownCaptureRatioExt<mode::Geometric> for T
+where
+    T: CandlesExt,
+{
+    fn down_capture_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        mode: mode::Geometric,
+        freq: usize,
+    ) -> Result<DownCaptureRatio<mode::Geometric>, Error> {
+        feed_all(
+            DownCaptureRatio::new(mode, freq)?,
+            aligned_ret(self, benchmark)?,
+        )
+    }
+}
+
+impl<T> DownCaptureRatioExt<mode::Simple> for T
+where
+    T: CandlesExt,
+{
+    fn down_capture_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        mode: mode::Simple,
+        freq: usize,
+    ) -> Result<DownCaptureRatio<mode::Simple>, Error> {
+        feed_all(
+            DownCaptureRatio::new(mode, freq)?,
+            aligned_ret(self, benchmark)?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::DownCaptureRatio;
+    use crate::{mode, Error, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+    static YS: [f64; 10] = [
+        -0.005, 0.081, 0.04, -0.037, -0.061, 0.058, -0.049, -0.021, 0.062, 0.058,
+    ];
+
+    #[test]
+    fn simple() {
+        let mut indicator = DownCaptureRatio::new(mode::Simple, 10).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(
+            f64,
+            -0.5144508670520231,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn geometric() {
+        let mut indicator = DownCaptureRatio::new(mode::Geometric, 10).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(
+            f64,
+            -0.502330915236667,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn no_down_period() {
+        let mut indicator = DownCaptureRatio::new(mode::Simple, 2).unwrap();
+        indicator.feed((0.01, 0.01)).unwrap();
+        assert_eq!(Err(Error::Undefined), indicator.feed((0.02, 0.0)));
+        assert_eq!(None, indicator.last());
+    }
+
+    #[test]
+    fn total_loss() {
+        let mut indicator = DownCaptureRatio::new(mode::Geometric, 2).unwrap();
+        indicator.feed((0.01, -0.5)).unwrap();
+        assert_eq!(Err(Error::Undefined), indicator.feed((-0.2, -1.0)));
+        assert_eq!(None, indicator.last());
+        assert_eq!(Err(Error::Undefined), indicator.feed((-0.1, -0.2)));
+        indicator.feed((-0.1, -0.2)).unwrap();
+        assert_approx_eq!(f64, 0.5, *indicator.last().unwrap(), epsilon = 1e-12);
+        let mut indicator = DownCaptureRatio::new(mode::Geometric, 2).unwrap();
+        indicator.feed((-1.5, -0.5)).unwrap();
+        assert_eq!(Err(Error::Undefined), indicator.feed((0.1, -0.1)));
+    }
+}
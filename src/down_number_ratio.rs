@@ -0,0 +1,152 @@
+use erfurt::candle::CandlesExt;
+
+use crate::{
+    aligned_ret, feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    paired::{HitRate, PairedWindow},
+    Error, Indicator,
+};
+
+/// Share of the periods in which the benchmark fell that the portfolio fell as
+/// well, fed as `(portfolio, benchmark)`. A window without such a period is
+/// rejected with [`Error::Undefined`].
+#[derive(Debug)]
+pub struct DownNumberRatio {
+    pub freq: usize,
+    window: PairedWindow,
+    hit_rate: HitRate,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl DownNumberRatio {
+    pub fn new(freq: usize) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            window: PairedWindow::new(freq),
+            hit_rate: HitRate::new(|b| b < 0.0, |p, _| p < 0.0),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for DownNumberRatio {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.window.feed(
+            &mut self.hit_rate,
+            &mut self.values,
+            (portfolio, benchmark),
+            HitRate::rate,
+        )
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait DownNumberRatioExt {
+    fn down_number_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+    ) -> Result<DownNumberRatio, Error>;
+}
+
+impl<T> DownNumberRatioExt for T
+where
+    T: CandlesExt,
+{
+    fn down_number_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+    ) -> Result<DownNumberRatio, Error> {
+        feed_all(DownNumberRatio::new(freq)?, aligned_ret(self, benchmark)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::DownNumberRatio;
+    use crate::{Error, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+    static YS: [f64; 10] = [
+        -0.005, 0.081, 0.04, -0.037, -0.061, 0.058, -0.049, -0.021, 0.062, 0.058,
+    ];
+
+    #[test]
+    fn down_number_ratio() {
+        let mut indicator = DownNumberRatio::new(10).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(f64, 0.2, *indicator.last().unwrap(), epsilon = 1e-9);
+    }
+
+    #[test]
+    fn no_matching_period() {
+        let mut indicator = DownNumberRatio::new(2).unwrap();
+        indicator.feed((0.01, 0.01)).unwrap();
+        assert_eq!(Err(Error::Undefined), indicator.feed((0.02, 0.0)));
+        assert_eq!(None, indicator.last());
+    }
+
+    #[test]
+    fn rolling() {
+        let mut indicator = DownNumberRatio::new(3).unwrap();
+        for x in [
+            (0.02, 0.01),
+            (-0.01, 0.02),
+            (0.01, -0.02),
+            (-0.03, -0.01),
+            (0.03, 0.02),
+        ] {
+            indicator.feed(x).unwrap();
+        }
+        let values: Vec<_> = indicator.iter().map(|x| x.copied()).collect();
+        assert_eq!(vec![None, None, Some(0.0), Some(0.5), Some(0.5)], values);
+    }
+}
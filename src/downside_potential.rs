@@ -1,66 +1,87 @@
-use crate::{Indicator, ReturnExt};
+use crate::{
+    feed_all,
+    history::{History, Values},
+    lower_partial_moment::LowerPartialMoment,
+    nan_policy::NanPolicy,
+    Error, Indicator, ReturnExt,
+};
 
 #[derive(Debug)]
 pub struct DownsidePotential {
     pub freq: usize,
     pub mar: f64,
-    input: Vec<f64>,
-    pub values: Vec<Option<f64>>,
+    lower_partial_moment: LowerPartialMoment,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
 }
 
 impl DownsidePotential {
-    pub fn new(freq: usize, mar: f64) -> Self {
-        Self {
+    pub fn new(freq: usize, mar: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
             freq,
             mar,
-            input: Vec::with_capacity(freq),
-            values: Vec::with_capacity(freq),
-        }
+            lower_partial_moment: LowerPartialMoment::new(freq, mar, 1)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
     }
 }
 
 impl Indicator for DownsidePotential {
     type Input = f64;
     type Output = f64;
-    fn feed(&mut self, ret: Self::Input) {
-        self.input.push(ret);
-        if self.input.len() >= self.freq {
-            let value = self.input[self.input.len() - self.freq..]
-                .iter()
-                .fold(0.0, |acc, x| {
-                    acc + (self.mar - x).max(0.0) / self.input.len() as f64
-                });
-            self.values.push(Some(value));
-        } else {
-            self.values.push(None)
-        }
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.lower_partial_moment.feed(ret)?;
+        self.values.push(self.lower_partial_moment.last().copied());
+        Ok(())
     }
 
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
 pub trait DownsidePotentialExt {
-    fn upside_potential(&self, freq: usize, mar: f64) -> Option<DownsidePotential>;
+    fn upside_potential(&self, freq: usize, mar: f64) -> Result<DownsidePotential, Error>;
 }
 
 impl<T> DownsidePotentialExt for T
 where
     T: ReturnExt,
 {
-    fn upside_potential(&self, freq: usize, mar: f64) -> Option<DownsidePotential> {
-        let mut indicator = DownsidePotential::new(freq, mar);
-        if let Some(ret) = self.ret() {
-            ret.iter().for_each(|&v| indicator.feed(v));
-            Some(indicator)
-        } else {
-            None
-        }
+    fn upside_potential(&self, freq: usize, mar: f64) -> Result<DownsidePotential, Error> {
+        feed_all(DownsidePotential::new(freq, mar)?, self.ret()?)
     }
 }
 
@@ -75,8 +96,20 @@ mod test {
     ];
     #[test]
     fn downside_potential() {
-        let mut indicator = DownsidePotential::new(10, 0.1 / 100.0);
-        XS.iter().for_each(|x| indicator.feed(*x));
+        let mut indicator = DownsidePotential::new(10, 0.1 / 100.0).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
         assert_approx_eq!(f64, 0.0025, *indicator.last().unwrap(), epsilon = 0.0000001);
     }
+    #[test]
+    fn rolling_matches_batch() {
+        let mar = 0.1 / 100.0;
+        let mut indicator = DownsidePotential::new(4, mar).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        for (i, value) in indicator.iter().enumerate().skip(3) {
+            let batch = XS[i - 3..=i]
+                .iter()
+                .fold(0.0, |acc, x| acc + (mar - x).max(0.0) / 4.0);
+            assert_approx_eq!(f64, batch, *value.unwrap(), epsilon = 0.0000001);
+        }
+    }
 }
@@ -0,0 +1,124 @@
+use crate::{
+    continuous_drawdown::ContinousDrawdown,
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    value_at_risk::quantile,
+    Error, Indicator, ReturnExt,
+};
+
+/// `alpha` quantile of the drawdowns in the window, zero when there are none.
+#[derive(Debug)]
+pub struct DrawdownAtRisk {
+    pub freq: usize,
+    pub alpha: f64,
+    continuous_drawdown: ContinousDrawdown,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl DrawdownAtRisk {
+    pub fn new(freq: usize, alpha: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        if !(alpha > 0.0 && alpha < 1.0) {
+            return Err(Error::InvalidParameter);
+        }
+        Ok(Self {
+            freq,
+            alpha,
+            continuous_drawdown: ContinousDrawdown::new(freq)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for DrawdownAtRisk {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.continuous_drawdown.feed(ret)?;
+        if let Some(xs) = self.continuous_drawdown.last() {
+            let value = if xs.is_empty() {
+                0.0
+            } else {
+                quantile(&mut xs.clone(), self.alpha)
+            };
+            self.values.push(Some(value));
+        } else {
+            self.values.push(None)
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait DrawdownAtRiskExt {
+    fn drawdown_at_risk(&self, freq: usize, alpha: f64) -> Result<DrawdownAtRisk, Error>;
+}
+
+impl<T> DrawdownAtRiskExt for T
+where
+    T: ReturnExt,
+{
+    fn drawdown_at_risk(&self, freq: usize, alpha: f64) -> Result<DrawdownAtRisk, Error> {
+        feed_all(DrawdownAtRisk::new(freq, alpha)?, self.ret()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::DrawdownAtRisk;
+    use crate::{Error, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn drawdown_at_risk() {
+        let mut indicator = DrawdownAtRisk::new(10, 0.5).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        assert_approx_eq!(f64, 0.0115, *indicator.last().unwrap(), epsilon = 1e-9);
+        assert_eq!(
+            Err(Error::InvalidParameter),
+            DrawdownAtRisk::new(10, 1.0).map(|_| ())
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{Error, ReturnExt};
+
+/// One peak-to-recovery drawdown of a return series. Indices refer to the
+/// returns the episode was computed from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DrawdownEpisode {
+    /// First period below the previous peak.
+    pub start: usize,
+    /// Period of the lowest value.
+    pub trough: usize,
+    /// Period the previous peak was regained, `None` if it never was.
+    pub end: Option<usize>,
+    /// Loss from the peak to the trough, as a positive fraction.
+    pub depth: f64,
+    /// Periods from `start` through `end`, or through the last period if the
+    /// drawdown has not recovered.
+    pub length: usize,
+    /// Periods from `trough` to `end`.
+    pub recovery_length: Option<usize>,
+}
+
+impl DrawdownEpisode {
+    /// Wall-clock time from the peak before `start` to `end`, or to the last
+    /// timestamp if the drawdown has not recovered. `time` holds the
+    /// timestamps of the returns the episode was computed from, so the peak
+    /// of an episode starting at the first return has no timestamp and the
+    /// result is `None`, as it is when `time` does not cover the episode.
+    pub fn duration(&self, time: &[DateTime<Utc>]) -> Option<Duration> {
+        let end = match self.end {
+            Some(end) => end,
+            None => time.len().checked_sub(1)?,
+        };
+        let peak = self.start.checked_sub(1)?;
+        Some(*time.get(end)? - *time.get(peak)?)
+    }
+
+    /// Wall-clock time from `trough` to `end`, `None` if the drawdown has not
+    /// recovered or `time` does not cover it.
+    pub fn recovery_duration(&self, time: &[DateTime<Utc>]) -> Option<Duration> {
+        Some(*time.get(self.end?)? - *time.get(self.trough)?)
+    }
+}
+
+/// Every drawdown of `ret` in chronological order.
+pub fn drawdown_episodes(ret: &[f64]) -> Result<Vec<DrawdownEpisode>, Error> {
+    if ret.is_empty() {
+        return Err(Error::EmptyInput);
+    }
+    if ret.iter().any(|x| !x.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+    let mut episodes = Vec::new();
+    let mut wealth = 1.0;
+    let mut peak = 1.0;
+    let mut current: Option<(usize, usize, f64)> = None;
+    for (i, x) in ret.iter().enumerate() {
+        wealth *= 1.0 + x;
+        if wealth >= peak {
+            if let Some((start, trough, low)) = current.take() {
+                episodes.push(DrawdownEpisode {
+                    start,
+                    trough,
+                    end: Some(i),
+                    depth: 1.0 - low / peak,
+                    length: i - start + 1,
+                    recovery_length: Some(i - trough),
+                });
+            }
+            peak = wealth;
+        } else {
+            match current {
+                Some((_, ref mut trough, ref mut low)) if wealth < *low => {
+                    *trough = i;
+                    *low = wealth;
+                }
+                Some(_) => {}
+                None => current = Some((i, i, wealth)),
+            }
+        }
+    }
+    if let Some((start, trough, low)) = current {
+        episodes.push(DrawdownEpisode {
+            start,
+            trough,
+            end: None,
+            depth: 1.0 - low / peak,
+            length: ret.len() - start,
+            recovery_length: None,
+        });
+    }
+    Ok(episodes)
+}
+
+/// The `n` deepest drawdowns of `ret`, deepest first.
+pub fn worst_drawdowns(ret: &[f64], n: usize) -> Result<Vec<DrawdownEpisode>, Error> {
+    let mut episodes = drawdown_episodes(ret)?;
+    episodes.sort_by(|a, b| b.depth.total_cmp(&a.depth));
+    episodes.truncate(n);
+    Ok(episodes)
+}
+
+pub trait DrawdownEpisodeExt {
+    fn drawdown_episodes(&self) -> Result<Vec<DrawdownEpisode>, Error>;
+    fn worst_drawdowns(&self, n: usize) -> Result<Vec<DrawdownEpisode>, Error>;
+}
+
+impl<T> DrawdownEpisodeExt for T
+where
+    T: ReturnExt,
+{
+    fn drawdown_episodes(&self) -> Result<Vec<DrawdownEpisode>, Error> {
+        drawdown_episodes(&self.ret()?)
+    }
+
+    fn worst_drawdowns(&self, n: usize) -> Result<Vec<DrawdownEpisode>, Error> {
+        worst_drawdowns(&self.ret()?, n)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use chrono::{Duration, TimeZone, Utc};
+
+    use super::{drawdown_episodes, worst_drawdowns};
+    use crate::Error;
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn episodes() {
+        let episodes = drawdown_episodes(&XS).unwrap();
+        assert_eq!(2, episodes.len());
+        assert_eq!(
+            (3, 3, Some(4)),
+            (episodes[0].start, episodes[0].trough, episodes[0].end)
+        );
+        assert_eq!(
+            (2, Some(1)),
+            (episodes[0].length, episodes[0].recovery_length)
+        );
+        assert_approx_eq!(f64, 0.009, episodes[0].depth, epsilon = 1e-12);
+        assert_eq!(
+            (8, 8, Some(9)),
+            (episodes[1].start, episodes[1].trough, episodes[1].end)
+        );
+        assert_approx_eq!(f64, 0.014, episodes[1].depth, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn unrecovered() {
+        let episodes = drawdown_episodes(&[0.1, -0.1, -0.1, 0.05, 0.1, -0.05]).unwrap();
+        assert_eq!(1, episodes.len());
+        assert_eq!(
+            (1, 2, None),
+            (episodes[0].start, episodes[0].trough, episodes[0].end)
+        );
+        assert_eq!((5, None), (episodes[0].length, episodes[0].recovery_length));
+        assert_approx_eq!(f64, 0.19, episodes[0].depth, epsilon = 1e-12);
+        let worst = worst_drawdowns(&XS, 1).unwrap();
+        assert_eq!(1, worst.len());
+        assert_eq!(8, worst[0].start);
+        assert_eq!(Err(Error::EmptyInput), drawdown_episodes(&[]));
+    }
+
+    #[test]
+    fn durations() {
+        let time: Vec<_> = (0..XS.len() as i64)
+            .map(|i| Utc.timestamp_opt(i * 86_400, 0).unwrap())
+            .collect();
+        let episodes = drawdown_episodes(&XS).unwrap();
+        assert_eq!(Some(Duration::days(2)), episodes[0].duration(&time));
+        assert_eq!(
+            Some(Duration::days(1)),
+            episodes[0].recovery_duration(&time)
+        );
+        let episodes = drawdown_episodes(&XS[..9]).unwrap();
+        assert_eq!(Some(Duration::days(1)), episodes[1].duration(&time[..9]));
+        assert_eq!(None, episodes[1].recovery_duration(&time[..9]));
+    }
+
+    #[test]
+    fn durations_out_of_range() {
+        let time: Vec<_> = (0..4)
+            .map(|i| Utc.timestamp_opt(i * 86_400, 0).unwrap())
+            .collect();
+        let episodes = drawdown_episodes(&XS).unwrap();
+        assert_eq!(None, episodes[0].duration(&[]));
+        assert_eq!(None, episodes[0].recovery_duration(&[]));
+        assert_eq!(None, episodes[0].duration(&time));
+        assert_eq!(None, episodes[0].recovery_duration(&time));
+    }
+
+    #[test]
+    fn duration_from_first_return() {
+        let time: Vec<_> = (0..3)
+            .map(|i| Utc.timestamp_opt(i * 86_400, 0).unwrap())
+            .collect();
+        let episodes = drawdown_episodes(&[-0.01, 0.0, 0.02]).unwrap();
+        assert_eq!((0, Some(2)), (episodes[0].start, episodes[0].end));
+        assert_eq!(None, episodes[0].duration(&time));
+        assert_eq!(
+            Some(Duration::days(2)),
+            episodes[0].recovery_duration(&time)
+        );
+    }
+}
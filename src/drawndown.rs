@@ -1,19 +1,40 @@
-use crate::{Indicator, ReturnExt};
+use crate::{
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    rolling::Window,
+    Error, Indicator, ReturnExt,
+};
 
 #[derive(Debug)]
 pub struct Drawndown {
     pub freq: usize,
-    input: Vec<f64>,
-    pub values: Vec<Option<f64>>,
+    input: Window,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
 }
 
 impl Drawndown {
-    pub fn new(freq: usize) -> Self {
-        Self {
-            freq,
-            input: Vec::with_capacity(freq),
-            values: Vec::with_capacity(freq),
+    pub fn new(freq: usize) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
         }
+        Ok(Self {
+            freq,
+            input: Window::new(freq),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
     }
 }
 
@@ -21,50 +42,64 @@ impl Indicator for Drawndown {
     type Input = f64;
     type Output = f64;
 
-    fn feed(&mut self, ret: Self::Input) {
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
         self.input.push(ret);
-        if self.input.len() >= self.freq {
-            let mut s = 1.0;
-            let mut mx = 1.0;
-            let mut r = Vec::with_capacity(self.freq);
-            for x in self.input[self.input.len() - self.freq..].iter() {
-                let v = (1.0 + x) * s;
-                mx = v.max(mx);
-                s = v;
-                let dr = (mx - v) / mx;
-                r.push(dr);
-            }
-            self.values.push(r.last().copied())
+        if self.input.is_full() {
+            self.values
+                .push(drawdown_path(self.input.iter()).last().copied())
         } else {
             self.values.push(None)
         }
+        Ok(())
     }
 
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
+/// Drawdown from the running peak after each return, the level before the
+/// first return counting as a peak.
+pub(crate) fn drawdown_path<'a>(xs: impl Iterator<Item = &'a f64>) -> Vec<f64> {
+    let mut s = 1.0;
+    let mut mx = 1.0;
+    let mut r = Vec::new();
+    for x in xs {
+        let v = (1.0 + x) * s;
+        mx = v.max(mx);
+        s = v;
+        let dr = (mx - v) / mx;
+        r.push(dr);
+    }
+    r
+}
+
 pub trait DrawdownExt {
-    fn drawdown(&self, freq: usize) -> Option<Drawndown>;
+    fn drawdown(&self, freq: usize) -> Result<Drawndown, Error>;
 }
 
 impl<T> DrawdownExt for T
 where
     T: ReturnExt,
 {
-    fn drawdown(&self, freq: usize) -> Option<Drawndown> {
-        let mut indicator = Drawndown::new(freq);
-        if let Some(ret) = self.ret() {
-            ret.iter().for_each(|v| indicator.feed(*v));
-            Some(indicator)
-        } else {
-            None
-        }
+    fn drawdown(&self, freq: usize) -> Result<Drawndown, Error> {
+        feed_all(Drawndown::new(freq)?, self.ret()?)
     }
 }
 
@@ -81,8 +116,8 @@ mod test {
 
     #[test]
     fn drawdown() {
-        let mut indicator = Drawndown::new(10);
-        XS.iter().for_each(|x| indicator.feed(*x));
+        let mut indicator = Drawndown::new(10).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
         assert_approx_eq!(f64, 0.0, *indicator.last().unwrap(), epsilon = 0.0000001)
     }
 }
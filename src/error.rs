@@ -0,0 +1,42 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The series to compute a metric from has no elements.
+    EmptyInput,
+    /// An indicator was created with a window of zero periods.
+    ZeroWindow,
+    /// An input was NaN or infinite and has been rejected.
+    NonFinite,
+    /// Two series that are consumed pairwise have different lengths.
+    LengthMismatch { left: usize, right: usize },
+    /// The window has no dispersion (or, for drawdown ratios, no drawdown), so
+    /// a ratio over it is undefined. The input is still consumed and its
+    /// output is recorded as `None`.
+    ZeroVariance,
+    /// The metric is undefined over the window, such as a capture ratio over a
+    /// window without a period in which the benchmark moved its way, or a
+    /// compounded mean after a total loss. The input is still consumed and its
+    /// output is recorded as `None`.
+    Undefined,
+    /// An indicator parameter is outside of its domain.
+    InvalidParameter,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::EmptyInput => write!(f, "input series is empty"),
+            Error::ZeroWindow => write!(f, "window length must be greater than zero"),
+            Error::NonFinite => write!(f, "input is not a finite number"),
+            Error::LengthMismatch { left, right } => {
+                write!(f, "series lengths differ: {left} != {right}")
+            }
+            Error::ZeroVariance => write!(f, "window has zero variance"),
+            Error::Undefined => write!(f, "metric is undefined over the window"),
+            Error::InvalidParameter => write!(f, "parameter is out of range"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,363 @@
+use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+
+use crate::{
+    feed_all,
+    history::{History, Values},
+    method,
+    nan_policy::NanPolicy,
+    rolling::{RollingMoments, Window},
+    value_at_risk::{cornish_fisher, quantile, skew_kurtosis, standard_normal_quantile},
+    Error, Indicator, ReturnExt,
+};
+
+/// Mean return at or below the [`ValueAtRisk`](crate::value_at_risk::ValueAtRisk)
+/// of the window, negative for a loss. `method::CornishFisher` gives the
+/// modified expected shortfall of Boudt, Peterson and Croux (2008).
+#[derive(Debug)]
+pub struct ExpectedShortfall<T> {
+    pub method: T,
+    pub freq: usize,
+    pub confidence: f64,
+    z: f64,
+    input: Window,
+    moments: RollingMoments,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl<T> ExpectedShortfall<T> {
+    pub fn new(method: T, freq: usize, confidence: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        if !(confidence > 0.0 && confidence < 1.0) {
+            return Err(Error::InvalidParameter);
+        }
+        Ok(Self {
+            method,
+            freq,
+            confidence,
+            z: standard_normal_quantile(1.0 - confidence),
+            input: Window::new(freq),
+            moments: RollingMoments::default(),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+fn standard_normal() -> Normal {
+    Normal::new(0.0, 1.0).unwrap()
+}
+
+/// `∫ x^power φ(x) dx` from minus infinity to `h`.
+fn ipower(power: u32, h: f64) -> f64 {
+    let normal = standard_normal();
+    let pdf = normal.pdf(h);
+    if power.is_multiple_of(2) {
+        let full: f64 = (1..=power / 2).map(|j| 2.0 * j as f64).product();
+        let mut prod = 1.0;
+        let mut value = full * pdf;
+        for i in 1..=power / 2 {
+            prod *= 2.0 * i as f64;
+            value += full / prod * h.powi(2 * i as i32) * pdf;
+        }
+        value
+    } else {
+        let full: f64 = (0..=(power - 1) / 2)
+            .map(|j| 2.0 * j as f64 + 1.0)
+            .product();
+        let mut prod = 1.0;
+        let mut value = -full * normal.cdf(h);
+        for i in 0..=(power - 1) / 2 {
+            prod *= 2.0 * i as f64 + 1.0;
+            value += full / prod * h.powi(2 * i as i32 + 1) * pdf;
+        }
+        value
+    }
+}
+
+/// Edgeworth-expanded tail expectation at the Cornish-Fisher quantile `g`,
+/// before dividing by the tail probability.
+fn modified_tail_expectation(g: f64, skew: f64, kurtosis: f64) -> f64 {
+    let pdf = standard_normal().pdf(g);
+    pdf + (ipower(4, g) - 6.0 * ipower(2, g) + 3.0 * pdf) * kurtosis / 24.0
+        + (ipower(3, g) - 3.0 * ipower(1, g)) * skew / 6.0
+        + (ipower(6, g) - 15.0 * ipower(4, g) + 45.0 * ipower(2, g) - 15.0 * pdf) * skew.powi(2)
+            / 72.0
+}
+
+impl Indicator for ExpectedShortfall<method::Historical> {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.input.push(ret);
+        if self.input.is_full() && !self.input.is_finite() {
+            self.values.push(Some(f64::NAN));
+        } else if self.input.is_full() {
+            let mut xs: Vec<f64> = self.input.iter().copied().collect();
+            let var = quantile(&mut xs, 1.0 - self.confidence);
+            let tail: Vec<f64> = xs.into_iter().take_while(|&x| x <= var).collect();
+            self.values
+                .push(Some(tail.iter().sum::<f64>() / tail.len() as f64));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+impl Indicator for ExpectedShortfall<method::Gaussian> {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        if let Some(old) = self.input.push(ret) {
+            self.moments.remove(old);
+        }
+        self.moments.add(ret);
+        if self.input.is_full() {
+            let tail = 1.0 - self.confidence;
+            let e = standard_normal().pdf(self.z) / tail;
+            let value = self.moments.mean() - e * self.moments.std_dev();
+            self.values.push(Some(value));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+impl Indicator for ExpectedShortfall<method::CornishFisher> {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        if let Some(old) = self.input.push(ret) {
+            self.moments.remove(old);
+        }
+        self.moments.add(ret);
+        if self.input.is_full() && !self.input.is_finite() {
+            self.values.push(Some(f64::NAN));
+        } else if self.input.is_full() {
+            let mean = self.moments.mean();
+            let std_dev = self.moments.std_dev();
+            if std_dev == 0.0 {
+                self.values.push(None);
+                return Err(Error::ZeroVariance);
+            }
+            let (skew, kurtosis) = skew_kurtosis(self.input.iter(), mean);
+            let g = cornish_fisher(self.z, skew, kurtosis);
+            let e = modified_tail_expectation(g, skew, kurtosis) / (1.0 - self.confidence);
+            self.values.push(Some(mean + (-e).min(g) * std_dev));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait ExpectedShortfallExt<T> {
+    fn expected_shortfall(
+        &self,
+        method: T,
+        freq: usize,
+        confidence: f64,
+    ) -> Result<ExpectedShortfall<T>, Error>;
+}
+
+impl<T> ExpectedShortfallExt<method::Historical> for T
+where
+    T: ReturnExt,
+{
+    fn expected_shortfall(
+        &self,
+        method: method::Historical,
+        freq: usize,
+        confidence: f64,
+    ) -> Result<ExpectedShortfall<method::Historical>, Error> {
+        feed_all(
+            ExpectedShortfall::new(method, freq, confidence)?,
+            self.ret()?,
+        )
+    }
+}
+
+impl<T> ExpectedShortfallExt<method::Gaussian> for T
+where
+    T: ReturnExt,
+{
+    fn expected_shortfall(
+        &self,
+        method: method::Gaussian,
+        freq: usize,
+        confidence: f64,
+    ) -> Result<ExpectedShortfall<method::Gaussian>, Error> {
+        feed_all(
+            ExpectedShortfall::new(method, freq, confidence)?,
+            self.ret()?,
+        )
+    }
+}
+
+impl<T> ExpectedShortfallExt<method::CornishFisher> for T
+where
+    T: ReturnExt,
+{
+    fn expected_shortfall(
+        &self,
+        method: method::CornishFisher,
+        freq: usize,
+        confidence: f64,
+    ) -> Result<ExpectedShortfall<method::CornishFisher>, Error> {
+        feed_all(
+            ExpectedShortfall::new(method, freq, confidence)?,
+            self.ret()?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::ExpectedShortfall;
+    use crate::{method, Error, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn historical() {
+        // On the grid -5.0%, -4.9%, ..., 4.9% the 95% quantile falls between
+        // the fifth and sixth worst returns, so the shortfall is the mean of
+        // the five worst: -4.8%.
+        let mut indicator = ExpectedShortfall::new(method::Historical, 100, 0.95).unwrap();
+        (0..100).for_each(|i| indicator.feed(-0.05 + 0.001 * i as f64).unwrap());
+        assert_approx_eq!(f64, -0.048, *indicator.last().unwrap(), epsilon = 1e-9);
+    }
+
+    #[test]
+    fn gaussian() {
+        let mut indicator = ExpectedShortfall::new(method::Gaussian, 10, 0.95).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        // 95% expected shortfall of the standard normal is 2.0627 standard
+        // deviations below the mean.
+        let std_dev = 0.023230487630602065;
+        assert_approx_eq!(
+            f64,
+            0.0179 - 2.062713 * std_dev,
+            *indicator.last().unwrap(),
+            epsilon = 1e-7
+        );
+    }
+
+    #[test]
+    fn modified() {
+        // Returns at the mean +/- 3% with probability 1/6 each have zero skew
+        // and zero excess kurtosis, where the modified expected shortfall of
+        // Boudt, Peterson and Croux (2008) reduces to the normal closed form
+        // `mean - sd * pdf(z) / (1 - p)`, with pdf(z) / 0.05 = 2.0627128.
+        let xs = [
+            -0.02, -0.02, 0.04, 0.04, 0.01, 0.01, 0.01, 0.01, 0.01, 0.01, 0.01, 0.01,
+        ];
+        let mut indicator = ExpectedShortfall::new(method::CornishFisher, 12, 0.95).unwrap();
+        xs.iter().for_each(|x| indicator.feed(*x).unwrap());
+        let std_dev = (4.0 * 0.03_f64.powi(2) / 11.0).sqrt();
+        assert_approx_eq!(
+            f64,
+            0.01 - 2.062712807507426 * std_dev,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn invalid_confidence() {
+        assert_eq!(
+            Err(Error::InvalidParameter),
+            ExpectedShortfall::new(method::Gaussian, 10, 1.0).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn rolls_into_flat_window() {
+        let mut indicator = ExpectedShortfall::new(method::CornishFisher, 3, 0.95).unwrap();
+        for x in [0.037, -0.021, 0.013, 0.01, 0.01] {
+            indicator.feed(x).unwrap();
+        }
+        assert_eq!(Err(Error::ZeroVariance), indicator.feed(0.01));
+    }
+}
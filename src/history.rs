@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+
+/// How many outputs an indicator retains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum History {
+    /// Keep every output since the indicator was created.
+    #[default]
+    Unbounded,
+    /// Keep only the most recent `n` outputs.
+    Last(usize),
+}
+
+/// Output storage shared by all indicators, bounded according to a [`History`].
+#[derive(Debug, Clone)]
+pub struct Values<T> {
+    history: History,
+    pushed: usize,
+    buf: VecDeque<Option<T>>,
+}
+
+impl<T> Values<T> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            history: History::Unbounded,
+            pushed: 0,
+            buf: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn history(&self) -> History {
+        self.history
+    }
+
+    /// Changes the retention policy, dropping the oldest outputs that no
+    /// longer fit.
+    pub fn set_history(&mut self, history: History) {
+        self.history = history;
+        if let History::Last(n) = history {
+            while self.buf.len() > n {
+                self.buf.pop_front();
+            }
+            self.buf.shrink_to(n);
+        }
+    }
+
+    pub fn push(&mut self, value: Option<T>) {
+        self.pushed += 1;
+        if let History::Last(n) = self.history {
+            if n == 0 {
+                return;
+            }
+            if self.buf.len() >= n {
+                self.buf.pop_front();
+            }
+        }
+        self.buf.push_back(value);
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.buf.back().and_then(Option::as_ref)
+    }
+
+    /// Number of outputs pushed since creation, retained or not.
+    pub fn len(&self) -> usize {
+        self.pushed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pushed == 0
+    }
+
+    /// Output for the `index`-th input, if it is still retained.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let first = self.pushed - self.buf.len();
+        index
+            .checked_sub(first)
+            .and_then(|i| self.buf.get(i))
+            .and_then(Option::as_ref)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Option<&T>> + '_ {
+        self.buf.iter().map(Option::as_ref)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{std::Std, Indicator};
+
+    use super::History;
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn bounded_history() {
+        let mut unbounded = Std::new(4).unwrap();
+        let mut bounded = Std::new(4).unwrap().with_history(History::Last(3));
+        XS.iter().for_each(|x| {
+            unbounded.feed(*x).unwrap();
+            bounded.feed(*x).unwrap();
+        });
+        assert_eq!(3, bounded.iter().count());
+        assert_eq!(XS.len(), bounded.len());
+        assert_eq!(None, bounded.get(XS.len() - 4));
+        assert_eq!(unbounded.get(XS.len() - 3), bounded.get(XS.len() - 3));
+        assert_eq!(unbounded.last(), bounded.last());
+        unbounded
+            .iter()
+            .skip(XS.len() - 3)
+            .zip(bounded.iter())
+            .for_each(|(x, y)| assert_eq!(x, y));
+    }
+}
@@ -0,0 +1,152 @@
+use erfurt::candle::CandlesExt;
+
+use crate::{
+    active_return::ActiveReturn,
+    aligned_ret, feed_all,
+    history::{History, Values},
+    mode,
+    nan_policy::NanPolicy,
+    tracking_error::TrackingError,
+    Error, Indicator,
+};
+
+/// Annualized active return over tracking error, fed as
+/// `(portfolio, benchmark)`.
+#[derive(Debug)]
+pub struct InformationRatio {
+    pub freq: usize,
+    pub periods_per_year: f64,
+    active_return: ActiveReturn<mode::Geometric>,
+    tracking_error: TrackingError,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl InformationRatio {
+    pub fn new(freq: usize, periods_per_year: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            periods_per_year,
+            active_return: ActiveReturn::new(mode::Geometric, freq, periods_per_year)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            tracking_error: TrackingError::new(freq, periods_per_year)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for InformationRatio {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.active_return.feed((portfolio, benchmark))?;
+        self.tracking_error.feed((portfolio, benchmark))?;
+        match (self.active_return.last(), self.tracking_error.last()) {
+            (Some(_), Some(&0.0)) => {
+                self.values.push(None);
+                return Err(Error::ZeroVariance);
+            }
+            (Some(active_return), Some(tracking_error)) => {
+                self.values.push(Some(active_return / tracking_error))
+            }
+            _ => self.values.push(None),
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait InformationRatioExt {
+    fn information_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+    ) -> Result<InformationRatio, Error>;
+}
+
+impl<T> InformationRatioExt for T
+where
+    T: CandlesExt,
+{
+    fn information_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+    ) -> Result<InformationRatio, Error> {
+        feed_all(
+            InformationRatio::new(freq, periods_per_year)?,
+            aligned_ret(self, benchmark)?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::InformationRatio;
+    use crate::Indicator;
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+    static YS: [f64; 10] = [
+        -0.005, 0.081, 0.04, -0.037, -0.061, 0.058, -0.049, -0.021, 0.062, 0.058,
+    ];
+
+    #[test]
+    fn information_ratio() {
+        let mut indicator = InformationRatio::new(10, 10.0).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.4015583383915258,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+}
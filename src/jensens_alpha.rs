@@ -0,0 +1,166 @@
+use erfurt::candle::CandlesExt;
+
+use crate::{
+    aligned_ret,
+    annualized_return::AnnualizedReturn,
+    beta::Beta,
+    feed_all,
+    history::{History, Values},
+    mode,
+    nan_policy::NanPolicy,
+    Error, Indicator,
+};
+
+/// Annualized portfolio return in excess of the CAPM prediction
+/// `risk_free + beta * (benchmark - risk_free)`, fed as
+/// `(portfolio, benchmark)`. `risk_free` is an annual rate.
+#[derive(Debug)]
+pub struct JensensAlpha {
+    pub freq: usize,
+    pub periods_per_year: f64,
+    pub risk_free: f64,
+    portfolio_return: AnnualizedReturn<mode::Geometric>,
+    benchmark_return: AnnualizedReturn<mode::Geometric>,
+    beta: Beta,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl JensensAlpha {
+    pub fn new(freq: usize, periods_per_year: f64, risk_free: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            periods_per_year,
+            risk_free,
+            portfolio_return: AnnualizedReturn::new(mode::Geometric, freq, periods_per_year)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            benchmark_return: AnnualizedReturn::new(mode::Geometric, freq, periods_per_year)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            beta: Beta::new(freq)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for JensensAlpha {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.portfolio_return.feed(portfolio)?;
+        self.benchmark_return.feed(benchmark)?;
+        if let Err(err) = self.beta.feed((portfolio, benchmark)) {
+            self.values.push(None);
+            return Err(err);
+        }
+        if let (Some(rp), Some(rb), Some(beta)) = (
+            self.portfolio_return.last(),
+            self.benchmark_return.last(),
+            self.beta.last(),
+        ) {
+            let value = rp - (self.risk_free + beta * (rb - self.risk_free));
+            self.values.push(Some(value));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait JensensAlphaExt {
+    fn jensens_alpha<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: f64,
+    ) -> Result<JensensAlpha, Error>;
+}
+
+impl<T> JensensAlphaExt for T
+where
+    T: CandlesExt,
+{
+    fn jensens_alpha<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: f64,
+    ) -> Result<JensensAlpha, Error> {
+        feed_all(
+            JensensAlpha::new(freq, periods_per_year, risk_free)?,
+            aligned_ret(self, benchmark)?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::JensensAlpha;
+    use crate::Indicator;
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+    static YS: [f64; 10] = [
+        -0.005, 0.081, 0.04, -0.037, -0.061, 0.058, -0.049, -0.021, 0.062, 0.058,
+    ];
+
+    #[test]
+    fn jensens_alpha() {
+        let mut indicator = JensensAlpha::new(10, 10.0, 0.02).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.1690582205374938,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{
+    feed_all,
+    history::{History, Values},
+    lower_partial_moment::LowerPartialMoment,
+    nan_policy::NanPolicy,
+    rolling::{RollingSum, Window},
+    Error, Indicator, ReturnExt,
+};
+
+/// Mean excess return over `mar` divided by the `order`-th root of the lower
+/// partial moment of that order. `Kappa` of order 1 is Omega minus one and of
+/// order 2 is the Sortino ratio.
+#[derive(Debug)]
+pub struct Kappa {
+    pub freq: usize,
+    pub mar: f64,
+    pub order: u32,
+    input: Window,
+    sum: RollingSum,
+    lower_partial_moment: LowerPartialMoment,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl Kappa {
+    pub fn new(freq: usize, mar: f64, order: u32) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        if order == 0 {
+            return Err(Error::InvalidParameter);
+        }
+        Ok(Self {
+            freq,
+            mar,
+            order,
+            input: Window::new(freq),
+            sum: RollingSum::default(),
+            lower_partial_moment: LowerPartialMoment::new(freq, mar, order)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for Kappa {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.lower_partial_moment.feed(ret)?;
+        if let Some(old) = self.input.push(ret) {
+            self.sum.remove(old);
+        }
+        self.sum.add(ret);
+        match self.lower_partial_moment.last() {
+            Some(&0.0) => {
+                self.values.push(None);
+                return Err(Error::ZeroVariance);
+            }
+            Some(&lpm) => {
+                let mean = self.sum.sum() / self.freq as f64;
+                let value = (mean - self.mar) / lpm.powf(1.0 / self.order as f64);
+                self.values.push(Some(value));
+            }
+            None => self.values.push(None),
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait KappaExt {
+    fn kappa(&self, freq: usize, mar: f64, order: u32) -> Result<Kappa, Error>;
+}
+
+impl<T> KappaExt for T
+where
+    T: ReturnExt,
+{
+    fn kappa(&self, freq: usize, mar: f64, order: u32) -> Result<Kappa, Error> {
+        feed_all(Kappa::new(freq, mar, order)?, self.ret()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::Kappa;
+    use crate::{omega_ratio::OmegaRatio, Error, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn kappa() {
+        let mar = 0.1 / 100.0;
+        let mut omega = OmegaRatio::new(4, mar).unwrap();
+        let mut kappa1 = Kappa::new(4, mar, 1).unwrap();
+        let mut kappa2 = Kappa::new(10, 0.0, 2).unwrap();
+        let mut kappa3 = Kappa::new(10, mar, 3).unwrap();
+        XS.iter().for_each(|x| {
+            let _ = omega.feed(*x);
+            let _ = kappa1.feed(*x);
+            kappa2.feed(*x).unwrap();
+            kappa3.feed(*x).unwrap();
+        });
+        for (o, k) in omega.iter().zip(kappa1.iter()) {
+            if let (Some(o), Some(k)) = (o, k) {
+                assert_approx_eq!(f64, o - 1.0, *k, epsilon = 0.0000001);
+            }
+        }
+        assert_approx_eq!(f64, 3.401051, *kappa2.last().unwrap(), epsilon = 0.0000001);
+        let lpm3 = (0.01_f64.powi(3) + 0.015_f64.powi(3)) / 10.0;
+        assert_approx_eq!(
+            f64,
+            (0.0179 - mar) / lpm3.cbrt(),
+            *kappa3.last().unwrap(),
+            epsilon = 0.0000001
+        );
+        assert_eq!(
+            Err(Error::InvalidParameter),
+            Kappa::new(10, mar, 0).map(|_| ())
+        );
+    }
+}
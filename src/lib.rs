@@ -1,30 +1,98 @@
+use core::cmp::Ordering;
+
+use chrono::{DateTime, Utc};
 use erfurt::candle::CandlesExt;
 use itertools::Itertools;
+
+use crate::timed::Timed;
 pub mod active_return;
 pub mod annualized_return;
 pub mod annualized_risk;
+pub mod appraisal_ratio;
 pub mod average_drawdown;
+pub mod average_recovery_time;
+pub mod batting_average;
+pub mod beta;
+pub mod burke_ratio;
 pub mod cagr;
+pub mod calmar_ratio;
+pub mod capture_ratio;
+pub mod conditional_drawdown_at_risk;
 pub mod continuous_drawdown;
+pub mod correlation;
+pub mod down_capture_ratio;
+pub mod down_number_ratio;
+pub mod down_percentage_ratio;
 pub mod downside_potential;
 pub mod downside_risk;
+pub mod drawdown_at_risk;
+pub mod drawdown_episode;
 pub mod drawndown;
+pub mod error;
+pub mod expected_shortfall;
+pub mod history;
+pub mod information_ratio;
+pub mod jensens_alpha;
+pub mod kappa;
+pub mod lower_partial_moment;
+pub mod m2;
+pub mod m2_excess;
+pub mod martin_ratio;
+pub mod max_drawdown_duration;
 pub mod maximum_drawdown;
+pub mod nan_policy;
+pub mod omega_ratio;
+pub mod pain_index;
+pub mod pain_ratio;
+mod paired;
+mod partial_moment;
 pub mod prelude;
+mod rolling;
 pub mod rolling_economic_drawdown;
 pub mod ror;
 pub mod rsi;
 pub mod sharpe_ratio;
 pub mod sortino_ratio;
+pub mod specific_risk;
 pub mod std;
+pub mod sterling_ratio;
+pub mod systematic_risk;
+pub mod time_under_water;
+pub mod timed;
+pub mod total_risk;
+pub mod tracking_error;
+pub mod treynor_ratio;
+pub mod ulcer_index;
+pub mod underwater_curve;
+pub mod up_capture_ratio;
+pub mod up_number_ratio;
+pub mod up_percentage_ratio;
+pub mod upper_partial_moment;
 pub mod upside_potential;
+pub mod upside_potential_ratio;
+pub mod value_at_risk;
+
+pub use crate::error::Error;
 
+/// A rolling metric fed one input at a time.
+///
+/// Every `feed` that returns `Ok` (or [`Error::ZeroVariance`] or
+/// [`Error::Undefined`]) records exactly one output, `None` while the window
+/// is filling or the value is undefined, so the `i`-th output always belongs
+/// to the `i`-th accepted input.
 pub trait Indicator {
     type Input;
     type Output;
-    fn feed(&mut self, first: Self::Input);
+    fn feed(&mut self, first: Self::Input) -> Result<(), Error>;
     fn last(&self) -> Option<&Self::Output>;
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_>;
+    /// Number of outputs recorded so far, including ones no longer retained.
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Output recorded for the `index`-th input, if computed and retained.
+    fn get(&self, index: usize) -> Option<&Self::Output>;
 }
 
 pub mod mode {
@@ -33,10 +101,25 @@ pub mod mode {
 
     #[derive(Clone, Debug)]
     pub struct Simple;
+
+    #[derive(Clone, Debug)]
+    pub struct Log;
+}
+
+pub mod method {
+    #[derive(Clone, Debug)]
+    pub struct Historical;
+
+    #[derive(Clone, Debug)]
+    pub struct Gaussian;
+
+    #[derive(Clone, Debug)]
+    pub struct CornishFisher;
 }
 
 pub trait ReturnExt {
-    fn ret(&self) -> Option<Vec<f64>>;
+    fn ret(&self) -> Result<Vec<f64>, Error>;
+    fn log_ret(&self) -> Result<Vec<f64>, Error>;
 }
 
 pub trait Value<'a> {
@@ -48,15 +131,464 @@ impl<T> ReturnExt for T
 where
     T: CandlesExt,
 {
-    fn ret(&self) -> Option<Vec<f64>> {
+    fn ret(&self) -> Result<Vec<f64>, Error> {
         if !self.time().is_empty() {
             let mut ret = vec![0.0];
             for (x, y) in self.close().iter().tuple_windows() {
                 ret.push(y / x - 1.0)
             }
-            Some(ret)
+            Ok(ret)
         } else {
-            None
+            Err(Error::EmptyInput)
+        }
+    }
+
+    fn log_ret(&self) -> Result<Vec<f64>, Error> {
+        if !self.time().is_empty() {
+            let mut ret = vec![0.0];
+            for (x, y) in self.close().iter().tuple_windows() {
+                ret.push((y / x).ln())
+            }
+            Ok(ret)
+        } else {
+            Err(Error::EmptyInput)
+        }
+    }
+}
+
+/// Simple return pairs `(portfolio, benchmark)` over the timestamps present in
+/// both series, the first pair being `(0.0, 0.0)` as in [`ReturnExt::ret`].
+pub(crate) fn aligned_ret<P, B>(portfolio: &P, benchmark: &B) -> Result<Vec<(f64, f64)>, Error>
+where
+    P: CandlesExt,
+    B: CandlesExt,
+{
+    let closes = align(
+        portfolio.time(),
+        portfolio.close(),
+        benchmark.time(),
+        benchmark.close(),
+    )?;
+    let mut ret = vec![(0.0, 0.0)];
+    for ((_, (x0, y0)), (_, (x1, y1))) in closes.into_iter().tuple_windows() {
+        ret.push((x1 / x0 - 1.0, y1 / y0 - 1.0))
+    }
+    Ok(ret)
+}
+
+/// Close pairs at the timestamps present in both series, which must be sorted.
+pub(crate) fn align(
+    first_time: &[DateTime<Utc>],
+    first_close: &[f64],
+    second_time: &[DateTime<Utc>],
+    second_close: &[f64],
+) -> Result<Timed<(f64, f64)>, Error> {
+    let (mut i, mut j) = (0, 0);
+    let mut closes = Vec::new();
+    while i < first_time.len() && j < second_time.len() {
+        match first_time[i].cmp(&second_time[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                closes.push((first_time[i], (first_close[i], second_close[j])));
+                i += 1;
+                j += 1;
+            }
         }
     }
+    if closes.is_empty() {
+        return Err(Error::EmptyInput);
+    }
+    Ok(closes)
+}
+
+/// Feeds every input into `indicator`, stopping at the first rejected one.
+/// Windows that merely could not be computed stay `None` in the output.
+pub(crate) fn feed_all<T, I>(mut indicator: T, inputs: I) -> Result<T, Error>
+where
+    T: Indicator,
+    I: IntoIterator<Item = T::Input>,
+{
+    for input in inputs {
+        match indicator.feed(input) {
+            Ok(()) | Err(Error::ZeroVariance | Error::Undefined) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(indicator)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        active_return::ActiveReturn, annualized_return::AnnualizedReturn,
+        annualized_risk::AnnualizedRisk, appraisal_ratio::AppraisalRatio,
+        average_drawdown::AverageDrawdown, average_recovery_time::AverageRecoveryTime,
+        batting_average::BattingAverage, beta::Beta, burke_ratio::BurkeRatio, cagr::CAGR,
+        calmar_ratio::CalmarRatio, capture_ratio::CaptureRatio,
+        conditional_drawdown_at_risk::ConditionalDrawdownAtRisk,
+        continuous_drawdown::ContinousDrawdown, correlation::Correlation,
+        down_capture_ratio::DownCaptureRatio, down_number_ratio::DownNumberRatio,
+        down_percentage_ratio::DownPercentageRatio, downside_potential::DownsidePotential,
+        downside_risk::DownsideRisk, drawdown_at_risk::DrawdownAtRisk, drawndown::Drawndown,
+        expected_shortfall::ExpectedShortfall, information_ratio::InformationRatio,
+        jensens_alpha::JensensAlpha, kappa::Kappa, lower_partial_moment::LowerPartialMoment,
+        m2::M2, m2_excess::M2Excess, martin_ratio::MartinRatio,
+        max_drawdown_duration::MaxDrawdownDuration, maximum_drawdown::MaximumDrawdown, method,
+        mode, nan_policy::NanPolicy, omega_ratio::OmegaRatio, pain_index::PainIndex,
+        pain_ratio::PainRatio, rolling_economic_drawdown::RollingEconomicDrawdown, ror::RoR,
+        rsi::RSI, sharpe_ratio::SharpeRatio, sortino_ratio::SortinoRatio,
+        specific_risk::SpecificRisk, std::Std, sterling_ratio::SterlingRatio,
+        systematic_risk::SystematicRisk, time_under_water::TimeUnderWater, total_risk::TotalRisk,
+        tracking_error::TrackingError, treynor_ratio::TreynorRatio, ulcer_index::UlcerIndex,
+        underwater_curve::UnderwaterCurve, up_capture_ratio::UpCaptureRatio,
+        up_number_ratio::UpNumberRatio, up_percentage_ratio::UpPercentageRatio,
+        upper_partial_moment::UpperPartialMoment, upside_potential::UpsidePotential,
+        upside_potential_ratio::UpsidePotentialRatio, value_at_risk::ValueAtRisk, Indicator,
+    };
+
+    static XS: [f64; 10] = [
+        0.003,
+        0.026,
+        0.015,
+        -0.009,
+        0.014,
+        0.024,
+        0.015,
+        0.066,
+        -0.014,
+        f64::NAN,
+    ];
+
+    fn assert_aligned<T: Indicator>(mut indicator: T, xs: impl Iterator<Item = T::Input>) {
+        let mut n = 0;
+        for x in xs {
+            let _ = indicator.feed(x);
+            n += 1;
+            assert_eq!(n, indicator.len());
+            assert_eq!(n, indicator.iter().count());
+            let same = match (indicator.last(), indicator.get(n - 1)) {
+                (Some(x), Some(y)) => core::ptr::eq(x, y),
+                (x, y) => x.is_none() && y.is_none(),
+            };
+            assert!(same);
+        }
+    }
+
+    #[test]
+    fn one_output_per_input() {
+        let freq = 4;
+        let p = NanPolicy::Skip;
+        let xs = || XS.iter().copied();
+        assert_aligned(Std::new(freq).unwrap().with_nan_policy(p), xs());
+        assert_aligned(
+            SharpeRatio::new(freq, 252.0, 0.0)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            SortinoRatio::new(freq, 252.0, 0.0, 0.0)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            AnnualizedReturn::new(mode::Geometric, freq, 252.0)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            AnnualizedReturn::new(mode::Simple, freq, 252.0)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            AnnualizedReturn::new(mode::Log, freq, 252.0)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            AnnualizedRisk::new(freq, 252.0).unwrap().with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            CAGR::new(mode::Geometric, freq, 252.0)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            CAGR::new(mode::Log, freq, 252.0)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            RoR::new(mode::Geometric, freq).unwrap().with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(RoR::new(mode::Log, freq).unwrap().with_nan_policy(p), xs());
+        assert_aligned(
+            DownsideRisk::new(freq, 0.0).unwrap().with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            DownsidePotential::new(freq, 0.0)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            UpsidePotential::new(freq, 0.0).unwrap().with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(Drawndown::new(freq).unwrap().with_nan_policy(p), xs());
+        assert_aligned(
+            ContinousDrawdown::new(freq).unwrap().with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(MaximumDrawdown::new(freq).unwrap().with_nan_policy(p), xs());
+        assert_aligned(AverageDrawdown::new(freq).unwrap().with_nan_policy(p), xs());
+        assert_aligned(
+            RollingEconomicDrawdown::new(freq)
+                .unwrap()
+                .with_nan_policy(p),
+            xs().map(|x| 1.0 + x),
+        );
+        assert_aligned(
+            CalmarRatio::new(freq, 252.0).unwrap().with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            SterlingRatio::new(freq, 252.0, 0.1)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            BurkeRatio::new(freq, 252.0).unwrap().with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(OmegaRatio::new(freq, 0.0).unwrap().with_nan_policy(p), xs());
+        assert_aligned(
+            UpsidePotentialRatio::new(freq, 0.0)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            LowerPartialMoment::new(freq, 0.0, 3)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            UpperPartialMoment::new(freq, 0.0, 3)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(Kappa::new(freq, 0.0, 3).unwrap().with_nan_policy(p), xs());
+        assert_aligned(
+            ValueAtRisk::new(method::Historical, freq, 0.95)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            ValueAtRisk::new(method::Gaussian, freq, 0.95)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            ValueAtRisk::new(method::CornishFisher, freq, 0.95)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            ExpectedShortfall::new(method::Historical, freq, 0.95)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            ExpectedShortfall::new(method::Gaussian, freq, 0.95)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            ExpectedShortfall::new(method::CornishFisher, freq, 0.95)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            DrawdownAtRisk::new(freq, 0.95).unwrap().with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            ConditionalDrawdownAtRisk::new(freq, 0.95)
+                .unwrap()
+                .with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(TimeUnderWater::new(freq).unwrap().with_nan_policy(p), xs());
+        assert_aligned(
+            MaxDrawdownDuration::new(freq).unwrap().with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            AverageRecoveryTime::new(freq).unwrap().with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(UlcerIndex::new(freq).unwrap().with_nan_policy(p), xs());
+        assert_aligned(PainIndex::new(freq).unwrap().with_nan_policy(p), xs());
+        assert_aligned(
+            MartinRatio::new(freq, 252.0).unwrap().with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(
+            PainRatio::new(freq, 252.0).unwrap().with_nan_policy(p),
+            xs(),
+        );
+        assert_aligned(UnderwaterCurve::new().with_nan_policy(p), xs());
+        assert_aligned(RSI::new(freq).unwrap().with_nan_policy(p), xs());
+        assert_aligned(
+            ActiveReturn::new(mode::Geometric, freq, 252.0)
+                .unwrap()
+                .with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            ActiveReturn::new(mode::Simple, freq, 252.0)
+                .unwrap()
+                .with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            ActiveReturn::new(mode::Log, freq, 252.0)
+                .unwrap()
+                .with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            UpCaptureRatio::new(mode::Geometric, freq)
+                .unwrap()
+                .with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            UpCaptureRatio::new(mode::Simple, freq)
+                .unwrap()
+                .with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            DownCaptureRatio::new(mode::Geometric, freq)
+                .unwrap()
+                .with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            DownCaptureRatio::new(mode::Simple, freq)
+                .unwrap()
+                .with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            CaptureRatio::new(mode::Geometric, freq)
+                .unwrap()
+                .with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            CaptureRatio::new(mode::Simple, freq)
+                .unwrap()
+                .with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            BattingAverage::new(freq).unwrap().with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            UpNumberRatio::new(freq).unwrap().with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            DownNumberRatio::new(freq).unwrap().with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            UpPercentageRatio::new(freq).unwrap().with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            DownPercentageRatio::new(freq).unwrap().with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            M2::new(freq, 252.0, 0.0).unwrap().with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            M2Excess::new(freq, 252.0, 0.0).unwrap().with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            AppraisalRatio::new(freq, 252.0, 0.0)
+                .unwrap()
+                .with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            SpecificRisk::new(freq, 252.0).unwrap().with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            SystematicRisk::new(freq, 252.0).unwrap().with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            TotalRisk::new(freq, 252.0).unwrap().with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            Beta::new(freq).unwrap().with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            Correlation::new(freq).unwrap().with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            TrackingError::new(freq, 252.0).unwrap().with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            InformationRatio::new(freq, 252.0)
+                .unwrap()
+                .with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            JensensAlpha::new(freq, 252.0, 0.0)
+                .unwrap()
+                .with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+        assert_aligned(
+            TreynorRatio::new(freq, 252.0, 0.0)
+                .unwrap()
+                .with_nan_policy(p),
+            xs().zip(xs().rev()),
+        );
+    }
 }
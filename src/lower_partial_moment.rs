@@ -0,0 +1,156 @@
+use crate::{
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    partial_moment::PartialMoment,
+    Error, Indicator, ReturnExt,
+};
+
+/// Mean of `max(mar - x, 0)^order` over the window. Order 0 is the share of
+/// returns below `mar`.
+#[derive(Debug)]
+pub struct LowerPartialMoment {
+    pub freq: usize,
+    pub mar: f64,
+    pub order: u32,
+    moment: PartialMoment,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl LowerPartialMoment {
+    pub fn new(freq: usize, mar: f64, order: u32) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            mar,
+            order,
+            moment: PartialMoment::lower(freq, mar, order),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for LowerPartialMoment {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.values.push(self.moment.push(ret));
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait LowerPartialMomentExt {
+    fn lower_partial_moment(
+        &self,
+        freq: usize,
+        mar: f64,
+        order: u32,
+    ) -> Result<LowerPartialMoment, Error>;
+}
+
+impl<T> LowerPartialMomentExt for T
+where
+    T: ReturnExt,
+{
+    fn lower_partial_moment(
+        &self,
+        freq: usize,
+        mar: f64,
+        order: u32,
+    ) -> Result<LowerPartialMoment, Error> {
+        feed_all(LowerPartialMoment::new(freq, mar, order)?, self.ret()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::LowerPartialMoment;
+    use crate::{nan_policy::NanPolicy, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn rolling_matches_batch() {
+        let mar = 0.1 / 100.0;
+        for order in 0..4 {
+            let mut indicator = LowerPartialMoment::new(4, mar, order).unwrap();
+            XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+            for (i, value) in indicator.iter().enumerate().skip(3) {
+                let batch = XS[i - 3..=i]
+                    .iter()
+                    .filter(|&&x| x < mar)
+                    .map(|x| (mar - x).powi(order as i32))
+                    .sum::<f64>()
+                    / 4.0;
+                assert_approx_eq!(f64, batch, *value.unwrap(), epsilon = 0.0000001);
+            }
+        }
+    }
+
+    #[test]
+    fn propagates_nan() {
+        let mut indicator = LowerPartialMoment::new(2, 0.0, 2)
+            .unwrap()
+            .with_nan_policy(NanPolicy::Propagate);
+        [0.01, f64::NAN, 0.02, -0.01]
+            .iter()
+            .for_each(|x| indicator.feed(*x).unwrap());
+        let values: Vec<_> = indicator.iter().collect();
+        assert!(values[1].unwrap().is_nan());
+        assert!(values[2].unwrap().is_nan());
+        assert_approx_eq!(f64, 0.00005, *values[3].unwrap(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn propagates_infinity() {
+        let mut indicator = LowerPartialMoment::new(2, 0.0, 2)
+            .unwrap()
+            .with_nan_policy(NanPolicy::Propagate);
+        [0.01, f64::NEG_INFINITY, 0.02, -0.01]
+            .iter()
+            .for_each(|x| indicator.feed(*x).unwrap());
+        let values: Vec<_> = indicator.iter().collect();
+        assert!(values[1].unwrap().is_nan());
+        assert!(values[2].unwrap().is_nan());
+        assert_approx_eq!(f64, 0.00005, *values[3].unwrap(), epsilon = 1e-12);
+    }
+}
@@ -0,0 +1,168 @@
+use erfurt::candle::CandlesExt;
+
+use crate::{
+    aligned_ret,
+    annualized_risk::AnnualizedRisk,
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    sharpe_ratio::SharpeRatio,
+    Error, Indicator,
+};
+
+/// Modigliani risk-adjusted return: the annualized return the portfolio would
+/// have earned at the benchmark's volatility, fed as `(portfolio, benchmark)`.
+/// `risk_free` is an annual rate.
+#[derive(Debug)]
+pub struct M2 {
+    pub freq: usize,
+    pub periods_per_year: f64,
+    pub risk_free: f64,
+    sharpe_ratio: SharpeRatio,
+    benchmark_risk: AnnualizedRisk,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl M2 {
+    pub fn new(freq: usize, periods_per_year: f64, risk_free: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            periods_per_year,
+            risk_free,
+            sharpe_ratio: SharpeRatio::new(freq, periods_per_year, risk_free)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            benchmark_risk: AnnualizedRisk::new(freq, periods_per_year)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for M2 {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.benchmark_risk.feed(benchmark)?;
+        if let Err(err) = self.sharpe_ratio.feed(portfolio) {
+            self.values.push(None);
+            return Err(err);
+        }
+        if let (Some(sharpe_ratio), Some(benchmark_risk)) =
+            (self.sharpe_ratio.last(), self.benchmark_risk.last())
+        {
+            let annualized_sharpe_ratio = sharpe_ratio * self.periods_per_year.sqrt();
+            self.values.push(Some(
+                self.risk_free + annualized_sharpe_ratio * benchmark_risk,
+            ));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait M2Ext {
+    fn m2<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: f64,
+    ) -> Result<M2, Error>;
+}
+
+impl<T> M2Ext for T
+where
+    T: CandlesExt,
+{
+    fn m2<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: f64,
+    ) -> Result<M2, Error> {
+        feed_all(
+            M2::new(freq, periods_per_year, risk_free)?,
+            aligned_ret(self, benchmark)?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::M2;
+    use crate::{Error, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+    static YS: [f64; 10] = [
+        -0.005, 0.081, 0.04, -0.037, -0.061, 0.058, -0.049, -0.021, 0.062, 0.058,
+    ];
+
+    #[test]
+    fn m2() {
+        let mut indicator = M2::new(10, 10.0, 0.02).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.3818765243790953,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn flat_portfolio() {
+        let mut indicator = M2::new(2, 10.0, 0.0).unwrap();
+        indicator.feed((0.01, 0.02)).unwrap();
+        assert_eq!(Err(Error::ZeroVariance), indicator.feed((0.01, -0.02)));
+        assert_eq!(None, indicator.last());
+    }
+}
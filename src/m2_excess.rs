@@ -0,0 +1,155 @@
+use erfurt::candle::CandlesExt;
+
+use crate::{
+    aligned_ret,
+    annualized_return::AnnualizedReturn,
+    feed_all,
+    history::{History, Values},
+    m2::M2,
+    mode,
+    nan_policy::NanPolicy,
+    Error, Indicator,
+};
+
+/// [`M2`] less the benchmark's annualized mean return, fed as
+/// `(portfolio, benchmark)`.
+#[derive(Debug)]
+pub struct M2Excess {
+    pub freq: usize,
+    pub periods_per_year: f64,
+    pub risk_free: f64,
+    m2: M2,
+    benchmark_return: AnnualizedReturn<mode::Simple>,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl M2Excess {
+    pub fn new(freq: usize, periods_per_year: f64, risk_free: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            periods_per_year,
+            risk_free,
+            m2: M2::new(freq, periods_per_year, risk_free)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            benchmark_return: AnnualizedReturn::new(mode::Simple, freq, periods_per_year)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for M2Excess {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.benchmark_return.feed(benchmark)?;
+        if let Err(err) = self.m2.feed((portfolio, benchmark)) {
+            self.values.push(None);
+            return Err(err);
+        }
+        if let (Some(m2), Some(benchmark_return)) = (self.m2.last(), self.benchmark_return.last()) {
+            self.values.push(Some(m2 - benchmark_return));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait M2ExcessExt {
+    fn m2_excess<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: f64,
+    ) -> Result<M2Excess, Error>;
+}
+
+impl<T> M2ExcessExt for T
+where
+    T: CandlesExt,
+{
+    fn m2_excess<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: f64,
+    ) -> Result<M2Excess, Error> {
+        feed_all(
+            M2Excess::new(freq, periods_per_year, risk_free)?,
+            aligned_ret(self, benchmark)?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::M2Excess;
+    use crate::Indicator;
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+    static YS: [f64; 10] = [
+        -0.005, 0.081, 0.04, -0.037, -0.061, 0.058, -0.049, -0.021, 0.062, 0.058,
+    ];
+
+    #[test]
+    fn m2_excess() {
+        let mut indicator = M2Excess::new(10, 10.0, 0.02).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.2558765243790953,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+}
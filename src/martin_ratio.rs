@@ -0,0 +1,133 @@
+use crate::{
+    annualized_return::AnnualizedReturn,
+    feed_all,
+    history::{History, Values},
+    mode,
+    nan_policy::NanPolicy,
+    ulcer_index::UlcerIndex,
+    Error, Indicator, ReturnExt,
+};
+
+#[derive(Debug)]
+pub struct MartinRatio {
+    pub freq: usize,
+    pub periods_per_year: f64,
+    annualized_return: AnnualizedReturn<mode::Geometric>,
+    ulcer_index: UlcerIndex,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl MartinRatio {
+    pub fn new(freq: usize, periods_per_year: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            periods_per_year,
+            annualized_return: AnnualizedReturn::new(mode::Geometric, freq, periods_per_year)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            ulcer_index: UlcerIndex::new(freq)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for MartinRatio {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.annualized_return.feed(ret)?;
+        self.ulcer_index.feed(ret)?;
+        match (self.annualized_return.last(), self.ulcer_index.last()) {
+            (Some(_), Some(&0.0)) => {
+                self.values.push(None);
+                return Err(Error::ZeroVariance);
+            }
+            (Some(annret), Some(ulcer_index)) => self.values.push(Some(annret / ulcer_index)),
+            _ => self.values.push(None),
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait MartinRatioExt {
+    fn martin_ratio(&self, freq: usize, periods_per_year: f64) -> Result<MartinRatio, Error>;
+}
+
+impl<T> MartinRatioExt for T
+where
+    T: ReturnExt,
+{
+    fn martin_ratio(&self, freq: usize, periods_per_year: f64) -> Result<MartinRatio, Error> {
+        feed_all(MartinRatio::new(freq, periods_per_year)?, self.ret()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::MartinRatio;
+    use crate::{Error, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn martin_ratio() {
+        let mut indicator = MartinRatio::new(10, 10.0).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.19135615147149543 / 0.005263078946776312,
+            *indicator.last().unwrap(),
+            epsilon = 0.0000001
+        );
+    }
+
+    #[test]
+    fn no_drawdown() {
+        let mut indicator = MartinRatio::new(2, 10.0).unwrap();
+        indicator.feed(0.01).unwrap();
+        assert_eq!(Err(Error::ZeroVariance), indicator.feed(0.02));
+        assert_eq!(None, indicator.last());
+    }
+}
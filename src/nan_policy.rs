@@ -0,0 +1,107 @@
+use crate::Error;
+
+/// What an indicator does with a NaN or infinite input.
+///
+/// Whatever the policy, every accepted input still produces exactly one
+/// output, so the output series stays aligned with the input series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NanPolicy {
+    /// Let the value into the window; every output computed over a window
+    /// containing it is NaN until it leaves the window.
+    Propagate,
+    /// Leave the window untouched and record a `None` output.
+    Skip,
+    /// Feed `0.0` in place of the value. Indicators fed prices rather than
+    /// returns repeat the last valid price instead, or record a `None` output
+    /// before there is one, since a price of zero would read as a total loss.
+    TreatAsZero,
+    /// Reject the input with [`Error::NonFinite`] without recording an output.
+    #[default]
+    Error,
+}
+
+impl NanPolicy {
+    /// Returns the value to feed, or `None` if the input should be skipped.
+    pub(crate) fn apply(self, x: f64) -> Result<Option<f64>, Error> {
+        if x.is_finite() {
+            return Ok(Some(x));
+        }
+        match self {
+            NanPolicy::Propagate => Ok(Some(x)),
+            NanPolicy::Skip => Ok(None),
+            NanPolicy::TreatAsZero => Ok(Some(0.0)),
+            NanPolicy::Error => Err(Error::NonFinite),
+        }
+    }
+
+    /// Like [`NanPolicy::apply`] for a price input, where
+    /// [`NanPolicy::TreatAsZero`] repeats `last`, the last valid price.
+    pub(crate) fn apply_price(self, x: f64, last: Option<f64>) -> Result<Option<f64>, Error> {
+        match self {
+            NanPolicy::TreatAsZero if !x.is_finite() => Ok(last),
+            _ => self.apply(x),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rsi::RSI, std::Std, Error, Indicator};
+
+    use super::NanPolicy;
+
+    static XS: [f64; 6] = [0.003, 0.026, f64::NAN, -0.009, 0.014, 0.024];
+
+    fn feed(policy: NanPolicy) -> (Std, Result<(), Error>) {
+        let mut indicator = Std::new(3).unwrap().with_nan_policy(policy);
+        let result = XS.iter().try_for_each(|x| indicator.feed(*x));
+        (indicator, result)
+    }
+
+    #[test]
+    fn propagate() {
+        let (indicator, result) = feed(NanPolicy::Propagate);
+        assert_eq!(Ok(()), result);
+        let values: Vec<_> = indicator.iter().collect();
+        assert_eq!(XS.len(), values.len());
+        assert!(values[2..5].iter().all(|v| v.unwrap().is_nan()));
+        assert!(values[5].unwrap().is_finite());
+    }
+
+    #[test]
+    fn skip() {
+        let (indicator, result) = feed(NanPolicy::Skip);
+        assert_eq!(Ok(()), result);
+        let values: Vec<_> = indicator.iter().collect();
+        assert_eq!(XS.len(), values.len());
+        assert_eq!(None, values[2]);
+        assert!(values[3..].iter().all(|v| v.unwrap().is_finite()));
+    }
+
+    #[test]
+    fn treat_as_zero() {
+        let (indicator, result) = feed(NanPolicy::TreatAsZero);
+        assert_eq!(Ok(()), result);
+        assert_eq!(XS.len(), indicator.iter().count());
+        assert!(indicator.iter().flatten().all(|v| v.is_finite()));
+    }
+
+    #[test]
+    fn error() {
+        let (indicator, result) = feed(NanPolicy::Error);
+        assert_eq!(Err(Error::NonFinite), result);
+        assert_eq!(2, indicator.iter().count());
+    }
+
+    #[test]
+    fn treat_as_zero_repeats_price() {
+        let mut indicator = RSI::new(2).unwrap().with_nan_policy(NanPolicy::TreatAsZero);
+        [f64::NAN, 10.0, 11.0, f64::NAN, 10.0]
+            .iter()
+            .for_each(|x| indicator.feed(*x).unwrap());
+        let values: Vec<_> = indicator.iter().collect();
+        assert_eq!(None, values[0]);
+        assert_eq!(Some(&0.0), values[3]);
+        assert_eq!(Some(&0.0), values[4]);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{
+    downside_potential::DownsidePotential,
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    upside_potential::UpsidePotential,
+    Error, Indicator, ReturnExt,
+};
+
+#[derive(Debug)]
+pub struct OmegaRatio {
+    pub freq: usize,
+    pub mar: f64,
+    upside_potential: UpsidePotential,
+    downside_potential: DownsidePotential,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl OmegaRatio {
+    pub fn new(freq: usize, mar: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            mar,
+            upside_potential: UpsidePotential::new(freq, mar)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            downside_potential: DownsidePotential::new(freq, mar)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for OmegaRatio {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.upside_potential.feed(ret)?;
+        self.downside_potential.feed(ret)?;
+        match (self.upside_potential.last(), self.downside_potential.last()) {
+            (Some(_), Some(&0.0)) => {
+                self.values.push(None);
+                return Err(Error::ZeroVariance);
+            }
+            (Some(upside), Some(downside)) => self.values.push(Some(upside / downside)),
+            _ => self.values.push(None),
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+/// Omega of the whole of `ret` at each of `thresholds`, paired with the
+/// threshold. Thresholds with no return below them have no Omega.
+pub fn omega_curve(ret: &[f64], thresholds: &[f64]) -> Result<Vec<(f64, Option<f64>)>, Error> {
+    if ret.is_empty() {
+        return Err(Error::EmptyInput);
+    }
+    if ret.iter().any(|x| !x.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+    Ok(thresholds
+        .iter()
+        .map(|&mar| {
+            let (upside, downside) = ret.iter().fold((0.0, 0.0), |(up, down), x| {
+                (up + (x - mar).max(0.0), down + (mar - x).max(0.0))
+            });
+            (mar, (downside != 0.0).then(|| upside / downside))
+        })
+        .collect())
+}
+
+pub trait OmegaRatioExt {
+    fn omega_ratio(&self, freq: usize, mar: f64) -> Result<OmegaRatio, Error>;
+    fn omega_curve(&self, thresholds: &[f64]) -> Result<Vec<(f64, Option<f64>)>, Error>;
+}
+
+impl<T> OmegaRatioExt for T
+where
+    T: ReturnExt,
+{
+    fn omega_ratio(&self, freq: usize, mar: f64) -> Result<OmegaRatio, Error> {
+        feed_all(OmegaRatio::new(freq, mar)?, self.ret()?)
+    }
+
+    fn omega_curve(&self, thresholds: &[f64]) -> Result<Vec<(f64, Option<f64>)>, Error> {
+        omega_curve(&self.ret()?, thresholds)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::{omega_curve, OmegaRatio};
+    use crate::{Error, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn omega_ratio() {
+        let mut indicator = OmegaRatio::new(10, 0.1 / 100.0).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.0194 / 0.0025,
+            *indicator.last().unwrap(),
+            epsilon = 0.0000001
+        );
+    }
+
+    #[test]
+    fn curve() {
+        let curve = omega_curve(&XS, &[-0.02, 0.0, 0.1 / 100.0, 0.01]).unwrap();
+        assert_eq!(4, curve.len());
+        assert_eq!((-0.02, None), curve[0]);
+        assert_approx_eq!(f64, 0.0194 / 0.0025, curve[2].1.unwrap(), epsilon = 1e-9);
+        assert!(curve[1].1.unwrap() > curve[2].1.unwrap());
+        assert!(curve[2].1.unwrap() > curve[3].1.unwrap());
+        assert_eq!(Err(Error::EmptyInput), omega_curve(&[], &[0.0]));
+    }
+}
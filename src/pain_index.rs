@@ -0,0 +1,110 @@
+use crate::{
+    drawndown::drawdown_path,
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    rolling::Window,
+    Error, Indicator, ReturnExt,
+};
+
+/// Mean of the drawdown path over the window.
+#[derive(Debug)]
+pub struct PainIndex {
+    pub freq: usize,
+    input: Window,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl PainIndex {
+    pub fn new(freq: usize) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            input: Window::new(freq),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for PainIndex {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.input.push(ret);
+        if self.input.is_full() {
+            let path = drawdown_path(self.input.iter());
+            let value = path.iter().sum::<f64>() / self.freq as f64;
+            self.values.push(Some(value));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait PainIndexExt {
+    fn pain_index(&self, freq: usize) -> Result<PainIndex, Error>;
+}
+
+impl<T> PainIndexExt for T
+where
+    T: ReturnExt,
+{
+    fn pain_index(&self, freq: usize) -> Result<PainIndex, Error> {
+        feed_all(PainIndex::new(freq)?, self.ret()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::PainIndex;
+    use crate::Indicator;
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn pain_index() {
+        let mut indicator = PainIndex::new(10).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        assert_approx_eq!(f64, 0.0023, *indicator.last().unwrap(), epsilon = 1e-9);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{
+    annualized_return::AnnualizedReturn,
+    feed_all,
+    history::{History, Values},
+    mode,
+    nan_policy::NanPolicy,
+    pain_index::PainIndex,
+    Error, Indicator, ReturnExt,
+};
+
+#[derive(Debug)]
+pub struct PainRatio {
+    pub freq: usize,
+    pub periods_per_year: f64,
+    annualized_return: AnnualizedReturn<mode::Geometric>,
+    pain_index: PainIndex,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl PainRatio {
+    pub fn new(freq: usize, periods_per_year: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            periods_per_year,
+            annualized_return: AnnualizedReturn::new(mode::Geometric, freq, periods_per_year)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            pain_index: PainIndex::new(freq)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for PainRatio {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.annualized_return.feed(ret)?;
+        self.pain_index.feed(ret)?;
+        match (self.annualized_return.last(), self.pain_index.last()) {
+            (Some(_), Some(&0.0)) => {
+                self.values.push(None);
+                return Err(Error::ZeroVariance);
+            }
+            (Some(annret), Some(pain_index)) => self.values.push(Some(annret / pain_index)),
+            _ => self.values.push(None),
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait PainRatioExt {
+    fn pain_ratio(&self, freq: usize, periods_per_year: f64) -> Result<PainRatio, Error>;
+}
+
+impl<T> PainRatioExt for T
+where
+    T: ReturnExt,
+{
+    fn pain_ratio(&self, freq: usize, periods_per_year: f64) -> Result<PainRatio, Error> {
+        feed_all(PainRatio::new(freq, periods_per_year)?, self.ret()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::PainRatio;
+    use crate::{Error, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn pain_ratio() {
+        let mut indicator = PainRatio::new(10, 10.0).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.19135615147149543 / 0.0023,
+            *indicator.last().unwrap(),
+            epsilon = 0.0000001
+        );
+    }
+
+    #[test]
+    fn no_drawdown() {
+        let mut indicator = PainRatio::new(2, 10.0).unwrap();
+        indicator.feed(0.01).unwrap();
+        assert_eq!(Err(Error::ZeroVariance), indicator.feed(0.02));
+        assert_eq!(None, indicator.last());
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{
+    history::Values,
+    rolling::{RollingProduct, RollingSum, Window},
+    Error,
+};
+
+/// Running state over the `(portfolio, benchmark)` pairs inside a
+/// [`PairedWindow`], updated as pairs enter and leave it.
+pub(crate) trait PairedStat {
+    fn add(&mut self, portfolio: f64, benchmark: f64);
+    fn remove(&mut self, portfolio: f64, benchmark: f64);
+}
+
+/// Portfolio and benchmark returns over the same rolling window.
+#[derive(Debug)]
+pub(crate) struct PairedWindow {
+    portfolio: Window,
+    benchmark: Window,
+}
+
+impl PairedWindow {
+    pub fn new(freq: usize) -> Self {
+        Self {
+            portfolio: Window::new(freq),
+            benchmark: Window::new(freq),
+        }
+    }
+
+    /// Pushes `(portfolio, benchmark)`, adds it to `stat` and removes the pair
+    /// that left the window, then records `value` of `stat`. A window holding
+    /// a non-finite return records NaN; `value` returning `None` is rejected
+    /// with [`Error::Undefined`].
+    pub fn feed<S: PairedStat>(
+        &mut self,
+        stat: &mut S,
+        values: &mut Values<f64>,
+        (portfolio, benchmark): (f64, f64),
+        value: fn(&S) -> Option<f64>,
+    ) -> Result<(), Error> {
+        if let (Some(old), Some(old_benchmark)) = (
+            self.portfolio.push(portfolio),
+            self.benchmark.push(benchmark),
+        ) {
+            stat.remove(old, old_benchmark);
+        }
+        stat.add(portfolio, benchmark);
+        if !self.portfolio.is_full() {
+            values.push(None);
+        } else if !self.portfolio.is_finite() || !self.benchmark.is_finite() {
+            values.push(Some(f64::NAN));
+        } else {
+            match value(stat) {
+                Some(value) => values.push(Some(value)),
+                None => {
+                    values.push(None);
+                    return Err(Error::Undefined);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Running sums and compounded growth of the pairs whose benchmark return
+/// satisfies `filter`.
+#[derive(Debug)]
+pub(crate) struct Capture {
+    filter: fn(f64) -> bool,
+    n: usize,
+    portfolio_sum: RollingSum,
+    benchmark_sum: RollingSum,
+    portfolio_product: RollingProduct,
+    benchmark_product: RollingProduct,
+}
+
+impl Capture {
+    pub fn new(filter: fn(f64) -> bool) -> Self {
+        Self {
+            filter,
+            n: 0,
+            portfolio_sum: RollingSum::default(),
+            benchmark_sum: RollingSum::default(),
+            portfolio_product: RollingProduct::default(),
+            benchmark_product: RollingProduct::default(),
+        }
+    }
+
+    /// Ratio of mean returns, `None` when no pair passed the filter.
+    pub fn simple(&self) -> Option<f64> {
+        if self.n == 0 {
+            return None;
+        }
+        Some(self.portfolio_sum.sum() / self.benchmark_sum.sum())
+    }
+
+    /// Ratio of geometric mean returns, `None` when no pair passed the filter,
+    /// when either side lost everything, or when the benchmark's mean is zero.
+    pub fn geometric(&self) -> Option<f64> {
+        if self.n == 0 {
+            return None;
+        }
+        let n = self.n as f64;
+        let portfolio = self.portfolio_product.product();
+        let benchmark = self.benchmark_product.product();
+        if portfolio <= 0.0 || benchmark <= 0.0 {
+            return None;
+        }
+        let base = benchmark.powf(1.0 / n) - 1.0;
+        if base == 0.0 {
+            return None;
+        }
+        Some((portfolio.powf(1.0 / n) - 1.0) / base)
+    }
+}
+
+impl PairedStat for Capture {
+    fn add(&mut self, portfolio: f64, benchmark: f64) {
+        if (self.filter)(benchmark) {
+            self.n += 1;
+            self.portfolio_sum.add(portfolio);
+            self.benchmark_sum.add(benchmark);
+            self.portfolio_product.add(portfolio);
+            self.benchmark_product.add(benchmark);
+        }
+    }
+
+    fn remove(&mut self, portfolio: f64, benchmark: f64) {
+        if (self.filter)(benchmark) {
+            self.n -= 1;
+            self.portfolio_sum.remove(portfolio);
+            self.benchmark_sum.remove(benchmark);
+            self.portfolio_product.remove(portfolio);
+            self.benchmark_product.remove(benchmark);
+        }
+    }
+}
+
+/// Counts of the pairs whose benchmark return satisfies `filter`, and of those
+/// among them for which `hit(portfolio, benchmark)` holds.
+#[derive(Debug)]
+pub(crate) struct HitRate {
+    filter: fn(f64) -> bool,
+    hit: fn(f64, f64) -> bool,
+    total: usize,
+    hits: usize,
+}
+
+impl HitRate {
+    pub fn new(filter: fn(f64) -> bool, hit: fn(f64, f64) -> bool) -> Self {
+        Self {
+            filter,
+            hit,
+            total: 0,
+            hits: 0,
+        }
+    }
+
+    /// Share of hits, `None` when no pair passed the filter.
+    pub fn rate(&self) -> Option<f64> {
+        if self.total == 0 {
+            return None;
+        }
+        Some(self.hits as f64 / self.total as f64)
+    }
+}
+
+impl PairedStat for HitRate {
+    fn add(&mut self, portfolio: f64, benchmark: f64) {
+        if (self.filter)(benchmark) {
+            self.total += 1;
+            self.hits += usize::from((self.hit)(portfolio, benchmark));
+        }
+    }
+
+    fn remove(&mut self, portfolio: f64, benchmark: f64) {
+        if (self.filter)(benchmark) {
+            self.total -= 1;
+            self.hits -= usize::from((self.hit)(portfolio, benchmark));
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use crate::rolling::{RollingSum, Window};
+
+/// Rolling mean of `max(sign * (mar - x), 0)^order`, shared by the lower
+/// (`sign = 1`) and upper (`sign = -1`) partial moments.
+#[derive(Debug)]
+pub(crate) struct PartialMoment {
+    sign: f64,
+    mar: f64,
+    order: u32,
+    input: Window,
+    sum: RollingSum,
+}
+
+impl PartialMoment {
+    pub fn lower(freq: usize, mar: f64, order: u32) -> Self {
+        Self::new(1.0, freq, mar, order)
+    }
+
+    pub fn upper(freq: usize, mar: f64, order: u32) -> Self {
+        Self::new(-1.0, freq, mar, order)
+    }
+
+    fn new(sign: f64, freq: usize, mar: f64, order: u32) -> Self {
+        Self {
+            sign,
+            mar,
+            order,
+            input: Window::new(freq),
+            sum: RollingSum::default(),
+        }
+    }
+
+    fn term(&self, x: f64) -> f64 {
+        if !x.is_finite() {
+            return f64::NAN;
+        }
+        let distance = self.sign * (self.mar - x);
+        if distance > 0.0 {
+            distance.powi(self.order as i32)
+        } else {
+            0.0
+        }
+    }
+
+    /// Pushes `x` and returns the moment once the window is full.
+    pub fn push(&mut self, x: f64) -> Option<f64> {
+        if let Some(old) = self.input.push(x) {
+            self.sum.remove(self.term(old));
+        }
+        self.sum.add(self.term(x));
+        self.input
+            .is_full()
+            .then(|| self.sum.sum() / self.input.len() as f64)
+    }
+}
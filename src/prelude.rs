@@ -1,3 +1,7 @@
+pub use crate::history::History;
+pub use crate::nan_policy::NanPolicy;
+pub use crate::timed::TimedExt;
+pub use crate::Error;
 pub use crate::Indicator;
 pub use crate::ReturnExt;
 pub use crate::Value;
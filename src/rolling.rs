@@ -0,0 +1,561 @@
+use std::collections::VecDeque;
+
+/// Fixed-length window over the most recent `freq` inputs.
+#[derive(Debug, Clone)]
+pub(crate) struct Window {
+    freq: usize,
+    non_finite: usize,
+    buf: VecDeque<f64>,
+}
+
+impl Window {
+    pub fn new(freq: usize) -> Self {
+        Self {
+            freq,
+            non_finite: 0,
+            buf: VecDeque::with_capacity(freq),
+        }
+    }
+
+    /// Pushes `x` and returns the input that left the window, if any.
+    pub fn push(&mut self, x: f64) -> Option<f64> {
+        let out = if self.buf.len() >= self.freq {
+            self.buf.pop_front()
+        } else {
+            None
+        };
+        if matches!(out, Some(old) if !old.is_finite()) {
+            self.non_finite -= 1;
+        }
+        if !x.is_finite() {
+            self.non_finite += 1;
+        }
+        self.buf.push_back(x);
+        out
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.non_finite == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.buf.len() >= self.freq
+    }
+
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    pub fn front(&self) -> Option<f64> {
+        self.buf.front().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &f64> + '_ {
+        self.buf.iter()
+    }
+}
+
+/// Running sum of the values currently inside a window.
+///
+/// Zero terms are not counted, so a window whose terms are all zero sums to
+/// exactly `0.0` regardless of the rounding left behind by earlier removals.
+/// Non-finite terms are counted but kept out of the sum, which reads as NaN
+/// until they have been removed again.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RollingSum {
+    terms: usize,
+    non_finite: usize,
+    sum: f64,
+}
+
+impl RollingSum {
+    pub fn add(&mut self, x: f64) {
+        if !x.is_finite() {
+            self.non_finite += 1;
+        } else if x != 0.0 {
+            self.terms += 1;
+            self.sum += x;
+        }
+    }
+
+    pub fn remove(&mut self, x: f64) {
+        if !x.is_finite() {
+            self.non_finite -= 1;
+        } else if x != 0.0 {
+            self.terms -= 1;
+            self.sum -= x;
+        }
+    }
+
+    pub fn sum(&self) -> f64 {
+        if self.non_finite > 0 {
+            f64::NAN
+        } else if self.terms == 0 {
+            0.0
+        } else {
+            self.sum
+        }
+    }
+}
+
+/// Length of the trailing run of equal finite values, which tells exactly
+/// whether the last `n` values fed are all the same.
+#[derive(Debug, Clone, Default)]
+struct Run {
+    last: f64,
+    len: usize,
+}
+
+impl Run {
+    fn add(&mut self, x: f64) {
+        if !x.is_finite() {
+            self.len = 0;
+        } else if self.len > 0 && x == self.last {
+            self.len += 1;
+        } else {
+            self.last = x;
+            self.len = 1;
+        }
+    }
+
+    fn covers(&self, n: usize) -> bool {
+        self.len >= n
+    }
+}
+
+/// Sum of squared deviations `m2` of `n` values around `mean`, flushed to zero
+/// when the values are all equal or `m2` is within rounding of their
+/// magnitude. Welford removal almost never brings `m2` back to exactly zero,
+/// so a window that rolls into a flat one would otherwise report a tiny
+/// positive variance.
+fn settle(m2: f64, n: usize, mean: f64, flat: bool) -> f64 {
+    if flat || m2 <= n as f64 * f64::EPSILON * mean * mean {
+        0.0
+    } else {
+        m2
+    }
+}
+
+/// Welford mean and sample variance supporting removal of old values.
+///
+/// Non-finite values are counted separately and make every moment NaN
+/// while they are inside the window. A window whose values are all equal has
+/// a variance of exactly `0.0`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RollingMoments {
+    n: usize,
+    non_finite: usize,
+    mean: f64,
+    m2: f64,
+    run: Run,
+}
+
+impl RollingMoments {
+    pub fn add(&mut self, x: f64) {
+        self.run.add(x);
+        if !x.is_finite() {
+            self.non_finite += 1;
+            return;
+        }
+        self.n += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.n as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    pub fn remove(&mut self, x: f64) {
+        if !x.is_finite() {
+            self.non_finite -= 1;
+            return;
+        }
+        if self.n <= 1 {
+            *self = Self {
+                non_finite: self.non_finite,
+                ..Self::default()
+            };
+            return;
+        }
+        self.n -= 1;
+        let delta = x - self.mean;
+        self.mean -= delta / self.n as f64;
+        self.m2 = (self.m2 - delta * (x - self.mean)).max(0.0);
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.n == 0 || self.non_finite > 0 {
+            f64::NAN
+        } else {
+            self.mean
+        }
+    }
+
+    pub fn variance(&self) -> f64 {
+        if self.n < 2 || self.non_finite > 0 {
+            f64::NAN
+        } else {
+            settle(self.m2, self.n, self.mean, self.run.covers(self.n)) / (self.n - 1) as f64
+        }
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+}
+
+/// Welford co-moments of paired values supporting removal of old pairs.
+///
+/// A pair with a non-finite member is counted separately and makes every
+/// moment NaN while it is inside the window. A side whose values are all
+/// equal has a variance, and a covariance with the other side, of exactly
+/// `0.0`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RollingCovariance {
+    n: usize,
+    non_finite: usize,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x: f64,
+    m2_y: f64,
+    c: f64,
+    run_x: Run,
+    run_y: Run,
+}
+
+impl RollingCovariance {
+    pub fn add(&mut self, x: f64, y: f64) {
+        let finite = x.is_finite() && y.is_finite();
+        self.run_x.add(if finite { x } else { f64::NAN });
+        self.run_y.add(if finite { y } else { f64::NAN });
+        if !x.is_finite() || !y.is_finite() {
+            self.non_finite += 1;
+            return;
+        }
+        self.n += 1;
+        let dx = x - self.mean_x;
+        let dy = y - self.mean_y;
+        self.mean_x += dx / self.n as f64;
+        self.mean_y += dy / self.n as f64;
+        self.m2_x += dx * (x - self.mean_x);
+        self.m2_y += dy * (y - self.mean_y);
+        self.c += dx * (y - self.mean_y);
+    }
+
+    pub fn remove(&mut self, x: f64, y: f64) {
+        if !x.is_finite() || !y.is_finite() {
+            self.non_finite -= 1;
+            return;
+        }
+        if self.n <= 1 {
+            *self = Self {
+                non_finite: self.non_finite,
+                ..Self::default()
+            };
+            return;
+        }
+        self.n -= 1;
+        let dx = x - self.mean_x;
+        let dy = y - self.mean_y;
+        self.mean_x -= dx / self.n as f64;
+        self.mean_y -= dy / self.n as f64;
+        self.m2_x = (self.m2_x - dx * (x - self.mean_x)).max(0.0);
+        self.m2_y = (self.m2_y - dy * (y - self.mean_y)).max(0.0);
+        self.c -= dx * (y - self.mean_y);
+    }
+
+    fn sample(&self, m: f64) -> f64 {
+        if self.n < 2 || self.non_finite > 0 {
+            f64::NAN
+        } else {
+            m / (self.n - 1) as f64
+        }
+    }
+
+    fn m2_x(&self) -> f64 {
+        settle(self.m2_x, self.n, self.mean_x, self.run_x.covers(self.n))
+    }
+
+    fn m2_y(&self) -> f64 {
+        settle(self.m2_y, self.n, self.mean_y, self.run_y.covers(self.n))
+    }
+
+    pub fn covariance(&self) -> f64 {
+        if self.m2_x() == 0.0 || self.m2_y() == 0.0 {
+            self.sample(0.0)
+        } else {
+            self.sample(self.c)
+        }
+    }
+
+    pub fn variance_x(&self) -> f64 {
+        self.sample(self.m2_x())
+    }
+
+    pub fn variance_y(&self) -> f64 {
+        self.sample(self.m2_y())
+    }
+}
+
+/// Running product of `1 + x` over the values currently inside a window.
+///
+/// Zero and non-finite factors are counted instead of multiplied in, so a
+/// total loss or a NaN can leave the window again without turning the
+/// product into `0 / 0`.
+#[derive(Debug, Clone)]
+pub(crate) struct RollingProduct {
+    zeros: usize,
+    non_finite: usize,
+    product: f64,
+}
+
+impl Default for RollingProduct {
+    fn default() -> Self {
+        Self {
+            zeros: 0,
+            non_finite: 0,
+            product: 1.0,
+        }
+    }
+}
+
+impl RollingProduct {
+    pub fn add(&mut self, x: f64) {
+        let factor = 1.0 + x;
+        if !factor.is_finite() {
+            self.non_finite += 1;
+        } else if factor == 0.0 {
+            self.zeros += 1;
+        } else {
+            self.product *= factor;
+        }
+    }
+
+    pub fn remove(&mut self, x: f64) {
+        let factor = 1.0 + x;
+        if !factor.is_finite() {
+            self.non_finite -= 1;
+        } else if factor == 0.0 {
+            self.zeros -= 1;
+        } else {
+            self.product /= factor;
+        }
+    }
+
+    pub fn product(&self) -> f64 {
+        if self.non_finite > 0 {
+            f64::NAN
+        } else if self.zeros > 0 {
+            0.0
+        } else {
+            self.product
+        }
+    }
+}
+
+/// Maximum of the last `freq` values, kept as a monotonic queue of candidates.
+///
+/// The maximum is NaN while a NaN is inside the window.
+#[derive(Debug, Clone)]
+pub(crate) struct RollingMax {
+    freq: usize,
+    seen: usize,
+    nans: VecDeque<usize>,
+    candidates: VecDeque<(usize, f64)>,
+}
+
+impl RollingMax {
+    pub fn new(freq: usize) -> Self {
+        Self {
+            freq,
+            seen: 0,
+            nans: VecDeque::new(),
+            candidates: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, x: f64) {
+        if x.is_nan() {
+            self.nans.push_back(self.seen);
+        } else {
+            while matches!(self.candidates.back(), Some(&(_, y)) if y <= x) {
+                self.candidates.pop_back();
+            }
+            self.candidates.push_back((self.seen, x));
+        }
+        self.seen += 1;
+        while matches!(self.candidates.front(), Some(&(i, _)) if i + self.freq < self.seen) {
+            self.candidates.pop_front();
+        }
+        while matches!(self.nans.front(), Some(&i) if i + self.freq < self.seen) {
+            self.nans.pop_front();
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.seen >= self.freq
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        if self.nans.is_empty() {
+            self.candidates.front().map(|&(_, x)| x)
+        } else {
+            Some(f64::NAN)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+    use statrs::statistics::Statistics;
+
+    use super::{
+        RollingCovariance, RollingMax, RollingMoments, RollingProduct, RollingSum, Window,
+    };
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn rolling_matches_batch() {
+        let freq = 4;
+        let mut window = Window::new(freq);
+        let mut sum = RollingSum::default();
+        let mut moments = RollingMoments::default();
+        let mut product = RollingProduct::default();
+        for (i, &x) in XS.iter().enumerate() {
+            if let Some(old) = window.push(x) {
+                sum.remove(old);
+                moments.remove(old);
+                product.remove(old);
+            }
+            sum.add(x);
+            moments.add(x);
+            product.add(x);
+            if window.is_full() {
+                let xs = &XS[i + 1 - freq..=i];
+                assert_approx_eq!(f64, xs.iter().sum::<f64>(), sum.sum(), epsilon = 1e-12);
+                assert_approx_eq!(f64, xs.iter().mean(), moments.mean(), epsilon = 1e-12);
+                assert_approx_eq!(f64, xs.iter().std_dev(), moments.std_dev(), epsilon = 1e-12);
+                assert_approx_eq!(
+                    f64,
+                    xs.iter().map(|x| 1.0 + x).product::<f64>(),
+                    product.product(),
+                    epsilon = 1e-12
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rolling_covariance_matches_batch() {
+        let freq = 4;
+        let ys: Vec<f64> = XS.iter().rev().map(|x| x * 2.0 - 0.01).collect();
+        let mut covariance = RollingCovariance::default();
+        for i in 0..XS.len() {
+            if i >= freq {
+                covariance.remove(XS[i - freq], ys[i - freq]);
+            }
+            covariance.add(XS[i], ys[i]);
+            if i + 1 >= freq {
+                let (xs, ys) = (&XS[i + 1 - freq..=i], &ys[i + 1 - freq..=i]);
+                let (mx, my) = (xs.iter().mean(), ys.iter().mean());
+                let batch = xs
+                    .iter()
+                    .zip(ys)
+                    .map(|(x, y)| (x - mx) * (y - my))
+                    .sum::<f64>()
+                    / (freq - 1) as f64;
+                assert_approx_eq!(f64, batch, covariance.covariance(), epsilon = 1e-12);
+                assert_approx_eq!(
+                    f64,
+                    xs.iter().variance(),
+                    covariance.variance_x(),
+                    epsilon = 1e-12
+                );
+                assert_approx_eq!(
+                    f64,
+                    ys.iter().variance(),
+                    covariance.variance_y(),
+                    epsilon = 1e-12
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn total_loss_leaves_window() {
+        let mut window = Window::new(2);
+        let mut product = RollingProduct::default();
+        for x in [0.1, -1.0, 0.2, 0.3] {
+            if let Some(old) = window.push(x) {
+                product.remove(old);
+            }
+            product.add(x);
+        }
+        assert_approx_eq!(f64, 1.2 * 1.3, product.product(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn rolling_max_matches_batch() {
+        let mut max = RollingMax::new(3);
+        for (i, &x) in XS.iter().enumerate() {
+            max.push(x);
+            if max.is_full() {
+                let batch = Statistics::max(XS[i - 2..=i].iter());
+                assert_approx_eq!(f64, batch, max.max().unwrap(), epsilon = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn non_finite_leaves_window() {
+        let freq = 2;
+        let mut window = Window::new(freq);
+        let mut sum = RollingSum::default();
+        let mut moments = RollingMoments::default();
+        let mut product = RollingProduct::default();
+        let mut max = RollingMax::new(freq);
+        for x in [0.1, f64::NAN, 0.2, 0.3] {
+            if let Some(old) = window.push(x) {
+                sum.remove(old);
+                moments.remove(old);
+                product.remove(old);
+            }
+            sum.add(x);
+            moments.add(x);
+            product.add(x);
+            max.push(x);
+            if !window.is_finite() {
+                assert!(sum.sum().is_nan());
+                assert!(moments.mean().is_nan());
+                assert!(product.product().is_nan());
+                assert!(max.max().unwrap().is_nan());
+            }
+        }
+        assert!(window.is_finite());
+        assert_approx_eq!(f64, 0.5, sum.sum(), epsilon = 1e-12);
+        assert_approx_eq!(f64, 0.25, moments.mean(), epsilon = 1e-12);
+        assert_approx_eq!(f64, 1.2 * 1.3, product.product(), epsilon = 1e-12);
+        assert_approx_eq!(f64, 0.3, max.max().unwrap(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn rolls_into_flat_window() {
+        for flat in [0.01, 0.0, 1e-9] {
+            let mut window = Window::new(3);
+            let mut moments = RollingMoments::default();
+            for x in [0.037, -0.021, 1.3, flat, flat, flat] {
+                if let Some(old) = window.push(x) {
+                    moments.remove(old);
+                }
+                moments.add(x);
+            }
+            assert_eq!(0.0, moments.variance());
+        }
+    }
+}
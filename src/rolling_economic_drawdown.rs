@@ -1,22 +1,44 @@
 use erfurt::candle::CandlesExt;
-use statrs::statistics::Statistics;
 
-use crate::Indicator;
+use crate::{
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    rolling::RollingMax,
+    Error, Indicator,
+};
 
 #[derive(Debug)]
 pub struct RollingEconomicDrawdown {
     pub freq: usize,
-    input: Vec<f64>,
-    pub values: Vec<Option<f64>>,
+    last_close: Option<f64>,
+    max: RollingMax,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
 }
 
 impl RollingEconomicDrawdown {
-    pub fn new(freq: usize) -> Self {
-        Self {
-            freq,
-            input: Vec::with_capacity(freq),
-            values: Vec::with_capacity(freq),
+    pub fn new(freq: usize) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
         }
+        Ok(Self {
+            freq,
+            last_close: None,
+            max: RollingMax::new(freq),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
     }
 }
 
@@ -24,42 +46,55 @@ impl Indicator for RollingEconomicDrawdown {
     type Input = f64;
     type Output = f64;
 
-    fn feed(&mut self, close: Self::Input) {
-        self.input.push(close);
-        if self.input.len() >= self.freq {
-            let mx = Statistics::max(self.input[self.input.len() - self.freq..].iter());
-            if let (Some(x), true) = (self.input.last(), !mx.is_nan()) {
-                self.values.push(Some(1.0 - (x / mx)));
-            }
+    fn feed(&mut self, close: Self::Input) -> Result<(), Error> {
+        let Some(close) = self.nan_policy.apply_price(close, self.last_close)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.last_close = Some(close);
+        self.max.push(close);
+        if self.max.is_full() {
+            let value = self.max.max().map(|mx| 1.0 - (close / mx));
+            self.values.push(value);
         } else {
             self.values.push(None)
         }
+        Ok(())
     }
 
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
 pub trait RollingEconomicDrawdownExt {
-    fn rolling_economic_drawndown(&self, freq: usize) -> Option<RollingEconomicDrawdown>;
+    fn rolling_economic_drawndown(&self, freq: usize) -> Result<RollingEconomicDrawdown, Error>;
 }
 
 impl<T> RollingEconomicDrawdownExt for T
 where
     T: CandlesExt,
 {
-    fn rolling_economic_drawndown(&self, freq: usize) -> Option<RollingEconomicDrawdown> {
+    fn rolling_economic_drawndown(&self, freq: usize) -> Result<RollingEconomicDrawdown, Error> {
         if !self.close().is_empty() {
-            let mut indicator = RollingEconomicDrawdown::new(freq);
-            self.close().iter().for_each(|&v| indicator.feed(v));
-            Some(indicator)
+            feed_all(
+                RollingEconomicDrawdown::new(freq)?,
+                self.close().iter().copied(),
+            )
         } else {
-            None
+            Err(Error::EmptyInput)
         }
     }
 }
@@ -68,7 +103,7 @@ where
 mod test {
     use float_cmp::assert_approx_eq;
 
-    use crate::Indicator;
+    use crate::{nan_policy::NanPolicy, Indicator};
 
     use super::RollingEconomicDrawdown;
     static XS: [f64; 10] = [
@@ -77,8 +112,8 @@ mod test {
 
     #[test]
     fn rolling_economic_drawndown() {
-        let mut indicator = RollingEconomicDrawdown::new(10);
-        XS.iter().for_each(|x| indicator.feed(*x));
+        let mut indicator = RollingEconomicDrawdown::new(10).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
         assert_approx_eq!(
             f64,
             0.40909090909090917,
@@ -86,4 +121,35 @@ mod test {
             epsilon = 0.0000001
         )
     }
+
+    #[test]
+    fn nan_keeps_alignment() {
+        let mut indicator = RollingEconomicDrawdown::new(3)
+            .unwrap()
+            .with_nan_policy(NanPolicy::Propagate);
+        [1.0, 2.0, f64::NAN, 1.5, 1.2, 1.0]
+            .iter()
+            .for_each(|x| indicator.feed(*x).unwrap());
+        let values: Vec<_> = indicator.iter().collect();
+        assert_eq!(6, values.len());
+        assert!(values[2..5].iter().all(|v| v.unwrap().is_nan()));
+        assert_approx_eq!(
+            f64,
+            1.0 - 1.0 / 1.5,
+            *values[5].unwrap(),
+            epsilon = 0.0000001
+        );
+    }
+
+    #[test]
+    fn treat_as_zero_repeats_close() {
+        let mut indicator = RollingEconomicDrawdown::new(2)
+            .unwrap()
+            .with_nan_policy(NanPolicy::TreatAsZero);
+        [f64::NAN, 2.0, 1.5, f64::NAN]
+            .iter()
+            .for_each(|x| indicator.feed(*x).unwrap());
+        let values: Vec<_> = indicator.iter().collect();
+        assert_eq!(vec![None, None, Some(&0.25), Some(&0.0)], values);
+    }
 }
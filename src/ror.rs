@@ -1,69 +1,156 @@
-use crate::{Indicator, ReturnExt};
+use crate::{
+    feed_all,
+    history::{History, Values},
+    mode,
+    nan_policy::NanPolicy,
+    rolling::{RollingProduct, RollingSum, Window},
+    Error, Indicator, ReturnExt,
+};
 
 #[derive(Debug)]
-pub struct RoR {
+pub struct RoR<T> {
+    pub mode: T,
     pub freq: usize,
-    input: Vec<f64>,
-    pub values: Vec<Option<f64>>,
+    input: Window,
+    // Compounds every input in the window except the oldest one, which only
+    // sets the starting level. New inputs are added before the new oldest one
+    // is removed, so with `freq == 1` an input is never removed unadded.
+    product: RollingProduct,
+    sum: RollingSum,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
 }
 
-impl RoR {
-    pub fn new(freq: usize) -> Self {
-        RoR {
+impl<T> RoR<T> {
+    pub fn new(mode: T, freq: usize) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(RoR {
+            mode,
             freq,
-            input: Vec::with_capacity(freq),
-            values: Vec::with_capacity(freq),
+            input: Window::new(freq),
+            product: RollingProduct::default(),
+            sum: RollingSum::default(),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for RoR<mode::Geometric> {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        if !self.input.is_empty() {
+            self.product.add(ret);
+        }
+        if self.input.push(ret).is_some() {
+            if let Some(front) = self.input.front() {
+                self.product.remove(front);
+            }
+        }
+        if self.input.is_full() {
+            self.values.push(Some(self.product.product() - 1.0));
+        } else {
+            self.values.push(None)
         }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
-impl Indicator for RoR {
+impl Indicator for RoR<mode::Log> {
     type Input = f64;
     type Output = f64;
 
-    fn feed(&mut self, ret: Self::Input) {
-        self.input.push(ret);
-        if self.input.len() >= self.freq {
-            let arr: Vec<f64> = self.input[self.input.len() - self.freq..]
-                .iter()
-                .map(|x| x + 1.0)
-                .scan(1.0, |acc, x| {
-                    *acc *= x;
-                    Some(*acc)
-                })
-                .collect();
-            let (x, y) = (arr.first().unwrap(), arr.last().unwrap());
-            self.values.push(Some(y / x - 1.0));
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        if !self.input.is_empty() {
+            self.sum.add(ret);
+        }
+        if self.input.push(ret).is_some() {
+            if let Some(front) = self.input.front() {
+                self.sum.remove(front);
+            }
+        }
+        if self.input.is_full() {
+            self.values.push(Some(self.sum.sum()));
         } else {
             self.values.push(None)
         }
+        Ok(())
     }
 
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
-pub trait RoRExt {
-    fn ror(&self, freq: usize) -> Option<RoR>;
+pub trait RoRExt<T> {
+    fn ror(&self, mode: T, freq: usize) -> Result<RoR<T>, Error>;
 }
 
-impl<T> RoRExt for T
+impl<T> RoRExt<mode::Geometric> for T
 where
     T: ReturnExt,
 {
-    fn ror(&self, freq: usize) -> Option<RoR> {
-        let mut indicator = RoR::new(freq);
-        if let Some(ret) = self.ret() {
-            ret.iter().for_each(|&v| indicator.feed(v));
-            Some(indicator)
-        } else {
-            None
-        }
+    fn ror(&self, mode: mode::Geometric, freq: usize) -> Result<RoR<mode::Geometric>, Error> {
+        feed_all(RoR::new(mode, freq)?, self.ret()?)
+    }
+}
+
+impl<T> RoRExt<mode::Log> for T
+where
+    T: ReturnExt,
+{
+    fn ror(&self, mode: mode::Log, freq: usize) -> Result<RoR<mode::Log>, Error> {
+        feed_all(RoR::new(mode, freq)?, self.log_ret()?)
     }
 }
 
@@ -71,15 +158,15 @@ where
 mod test {
     use float_cmp::assert_approx_eq;
 
-    use crate::{ror::RoR, Indicator};
+    use crate::{mode, nan_policy::NanPolicy, ror::RoR, Indicator};
 
     static XS: [f64; 10] = [
         0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
     ];
     #[test]
     fn ror() {
-        let mut indicator = RoR::new(10);
-        XS.iter().for_each(|x| indicator.feed(*x));
+        let mut indicator = RoR::new(mode::Geometric, 10).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
         assert_approx_eq!(
             f64,
             0.187793,
@@ -87,4 +174,39 @@ mod test {
             epsilon = 0.000001
         );
     }
+    #[test]
+    fn rolling_matches_batch() {
+        let mut indicator = RoR::new(mode::Geometric, 4).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        for (i, value) in indicator.iter().enumerate().skip(3) {
+            let batch = XS[i - 2..=i].iter().map(|x| 1.0 + x).product::<f64>() - 1.0;
+            assert_approx_eq!(f64, batch, *value.unwrap(), epsilon = 0.0000001);
+        }
+    }
+    #[test]
+    fn log() {
+        let mut indicator = RoR::new(mode::Log, 10).unwrap();
+        XS.iter().for_each(|x| indicator.feed(x.ln_1p()).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.187793_f64.ln_1p(),
+            *indicator.last().unwrap(),
+            epsilon = 0.000001
+        );
+    }
+    #[test]
+    fn single_period() {
+        let mut geometric = RoR::new(mode::Geometric, 1)
+            .unwrap()
+            .with_nan_policy(NanPolicy::Propagate);
+        let mut log = RoR::new(mode::Log, 1)
+            .unwrap()
+            .with_nan_policy(NanPolicy::Propagate);
+        for x in [0.1, -1.0, f64::NAN, 0.2] {
+            geometric.feed(x).unwrap();
+            log.feed(x).unwrap();
+        }
+        assert!(geometric.iter().all(|x| x == Some(&0.0)));
+        assert!(log.iter().all(|x| x == Some(&0.0)));
+    }
 }
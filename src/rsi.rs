@@ -1,25 +1,50 @@
 use erfurt::candle::CandlesExt;
 
-use crate::Indicator;
+use crate::{
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    rolling::{RollingSum, Window},
+    Error, Indicator,
+};
 
 #[derive(Debug)]
 pub struct RSI {
     pub freq: usize,
-    input: Vec<f64>,
-    gains: Vec<f64>,
-    losses: Vec<f64>,
-    pub values: Vec<Option<f64>>,
+    last_price: Option<f64>,
+    gains: Window,
+    losses: Window,
+    gains_sum: RollingSum,
+    losses_sum: RollingSum,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
 }
 
 impl RSI {
-    pub fn new(freq: usize) -> RSI {
-        RSI {
-            freq,
-            input: Vec::with_capacity(freq),
-            gains: Vec::with_capacity(freq),
-            losses: Vec::with_capacity(freq),
-            values: Vec::with_capacity(freq),
+    pub fn new(freq: usize) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
         }
+        Ok(RSI {
+            freq,
+            last_price: None,
+            gains: Window::new(freq),
+            losses: Window::new(freq),
+            gains_sum: RollingSum::default(),
+            losses_sum: RollingSum::default(),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
     }
 }
 
@@ -27,29 +52,28 @@ impl Indicator for RSI {
     type Input = f64;
     type Output = f64;
 
-    fn feed(&mut self, price: Self::Input) {
-        if let Some(last_price) = self.input.last() {
-            if price > *last_price {
-                self.gains.push(price - last_price);
-                self.losses.push(0.0);
-            } else {
-                self.gains.push(0.0);
-                self.losses.push(last_price - price);
-            }
-        } else {
-            self.gains.push(0.0);
-            self.losses.push(0.0);
+    fn feed(&mut self, price: Self::Input) -> Result<(), Error> {
+        let Some(price) = self.nan_policy.apply_price(price, self.last_price)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        let (gain, loss) = match self.last_price {
+            Some(last_price) if price > last_price => (price - last_price, 0.0),
+            Some(last_price) => (0.0, last_price - price),
+            None => (0.0, 0.0),
+        };
+        self.last_price = Some(price);
+        if let Some(old) = self.gains.push(gain) {
+            self.gains_sum.remove(old);
+        }
+        self.gains_sum.add(gain);
+        if let Some(old) = self.losses.push(loss) {
+            self.losses_sum.remove(old);
         }
-        self.input.push(price);
-        if self.input.len() >= self.freq {
-            let avg_gain = self.gains[self.gains.len() - self.freq..]
-                .iter()
-                .sum::<f64>()
-                / self.freq as f64;
-            let avg_loss = self.losses[self.losses.len() - self.freq..]
-                .iter()
-                .sum::<f64>()
-                / self.freq as f64;
+        self.losses_sum.add(loss);
+        if self.gains.is_full() {
+            let avg_gain = self.gains_sum.sum() / self.freq as f64;
+            let avg_loss = self.losses_sum.sum() / self.freq as f64;
             let rs = if avg_loss != 0.0 {
                 avg_gain / avg_loss
             } else {
@@ -60,28 +84,39 @@ impl Indicator for RSI {
         } else {
             self.values.push(None);
         }
+        Ok(())
     }
 
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
 pub trait RsiExt {
-    fn rsi(&self, freq: usize) -> Option<RSI>;
+    fn rsi(&self, freq: usize) -> Result<RSI, Error>;
 }
 
 impl<T> RsiExt for T
 where
     T: CandlesExt,
 {
-    fn rsi(&self, freq: usize) -> Option<RSI> {
-        let mut indicator = RSI::new(freq);
-        self.close().iter().for_each(|v| indicator.feed(*v));
-        Some(indicator)
+    fn rsi(&self, freq: usize) -> Result<RSI, Error> {
+        if !self.close().is_empty() {
+            feed_all(RSI::new(freq)?, self.close().iter().copied())
+        } else {
+            Err(Error::EmptyInput)
+        }
     }
 }
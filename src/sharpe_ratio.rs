@@ -1,82 +1,174 @@
-use statrs::statistics::Statistics;
-
-use crate::{Indicator, ReturnExt};
+use crate::{
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    rolling::{RollingMoments, Window},
+    Error, Indicator, ReturnExt,
+};
 
 #[derive(Debug)]
 pub struct SharpeRatio {
     pub freq: usize,
+    pub periods_per_year: f64,
     pub risk_free: f64,
-    pub input: Vec<f64>,
-    pub values: Vec<Option<f64>>,
+    input: Window,
+    moments: RollingMoments,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
 }
 
 impl SharpeRatio {
-    pub fn new(freq: usize, risk_free: f64) -> Self {
-        Self {
+    pub fn new(freq: usize, periods_per_year: f64, risk_free: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        if !(periods_per_year.is_finite() && periods_per_year > 0.0) {
+            return Err(Error::InvalidParameter);
+        }
+        Ok(Self {
             freq,
+            periods_per_year,
             risk_free,
-            input: Vec::with_capacity(freq),
-            values: Vec::with_capacity(freq),
+            input: Window::new(freq),
+            moments: RollingMoments::default(),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+
+    /// Feeds `ret` together with the risk-free rate for the same period,
+    /// ignoring the constant `risk_free`.
+    pub fn feed_with_risk_free(&mut self, ret: f64, risk_free: f64) -> Result<(), Error> {
+        let (Some(ret), Some(risk_free)) = (
+            self.nan_policy.apply(ret)?,
+            self.nan_policy.apply(risk_free)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        let excess = ret - risk_free;
+        if let Some(old) = self.input.push(excess) {
+            self.moments.remove(old);
         }
+        self.moments.add(excess);
+        if self.input.is_full() {
+            let std_dev = self.moments.std_dev();
+            if std_dev == 0.0 {
+                self.values.push(None);
+                return Err(Error::ZeroVariance);
+            }
+            self.values.push(Some(self.moments.mean() / std_dev));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
     }
 }
 
 impl Indicator for SharpeRatio {
     type Input = f64;
     type Output = f64;
-    fn feed(&mut self, ret: Self::Input) {
-        self.input.push(ret);
-        if self.input.len() >= self.freq {
-            let xs = &self.input[self.input.len() - self.freq..];
-            let risk_free_per_period = (1.0 + self.risk_free).powf(1.0 / self.freq as f64) - 1.0;
-            let value = (xs.iter().mean() - risk_free_per_period) / xs.iter().std_dev();
-            self.values.push(Some(value));
-        } else {
-            self.values.push(None);
-        }
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let risk_free = (1.0 + self.risk_free).powf(1.0 / self.periods_per_year) - 1.0;
+        self.feed_with_risk_free(ret, risk_free)
     }
 
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
 pub trait SharpeRatioExt {
-    fn sharpe_ratio(&self, freq: usize, risk_free: f64) -> Option<SharpeRatio>;
+    fn sharpe_ratio(
+        &self,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: f64,
+    ) -> Result<SharpeRatio, Error>;
+    fn sharpe_ratio_with_risk_free(
+        &self,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: &[f64],
+    ) -> Result<SharpeRatio, Error>;
 }
 
 impl<T> SharpeRatioExt for T
 where
     T: ReturnExt,
 {
-    fn sharpe_ratio(&self, freq: usize, risk_free: f64) -> Option<SharpeRatio> {
-        let mut indicator = SharpeRatio::new(freq, risk_free);
-        if let Some(ret) = self.ret() {
-            ret.iter().for_each(|&v| indicator.feed(v));
-            Some(indicator)
-        } else {
-            None
+    fn sharpe_ratio(
+        &self,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: f64,
+    ) -> Result<SharpeRatio, Error> {
+        feed_all(
+            SharpeRatio::new(freq, periods_per_year, risk_free)?,
+            self.ret()?,
+        )
+    }
+
+    fn sharpe_ratio_with_risk_free(
+        &self,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: &[f64],
+    ) -> Result<SharpeRatio, Error> {
+        let ret = self.ret()?;
+        if ret.len() != risk_free.len() {
+            return Err(Error::LengthMismatch {
+                left: ret.len(),
+                right: risk_free.len(),
+            });
+        }
+        let mut indicator = SharpeRatio::new(freq, periods_per_year, 0.0)?;
+        for (&ret, &risk_free) in ret.iter().zip(risk_free) {
+            match indicator.feed_with_risk_free(ret, risk_free) {
+                Ok(()) | Err(Error::ZeroVariance) => {}
+                Err(err) => return Err(err),
+            }
         }
+        Ok(indicator)
     }
 }
 
 #[cfg(test)]
 mod test {
     use float_cmp::assert_approx_eq;
+    use statrs::statistics::Statistics;
 
-    use crate::{sharpe_ratio::SharpeRatio, Indicator};
+    use crate::{sharpe_ratio::SharpeRatio, Error, Indicator};
 
     static XS: [f64; 10] = [
         0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
     ];
     #[test]
     fn sharpe_ratio() {
-        let mut indicator = SharpeRatio::new(10, 0.0);
-        XS.iter().for_each(|x| indicator.feed(*x));
+        let mut indicator = SharpeRatio::new(10, 10.0, 0.0).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
         assert_approx_eq!(
             f64,
             0.7705391,
@@ -84,4 +176,74 @@ mod test {
             epsilon = 0.0000001
         );
     }
+
+    #[test]
+    fn rolling_matches_batch() {
+        let mut indicator = SharpeRatio::new(4, 252.0, 0.0).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        for (i, value) in indicator.iter().enumerate().skip(3) {
+            let xs = &XS[i - 3..=i];
+            assert_approx_eq!(
+                f64,
+                xs.iter().mean() / xs.iter().std_dev(),
+                *value.unwrap(),
+                epsilon = 0.0000001
+            );
+        }
+    }
+    #[test]
+    fn risk_free_series() {
+        let risk_free: Vec<f64> = (0..XS.len()).map(|i| 0.001 * i as f64).collect();
+        let mut indicator = SharpeRatio::new(4, 252.0, 0.0).unwrap();
+        XS.iter()
+            .zip(&risk_free)
+            .for_each(|(x, rf)| indicator.feed_with_risk_free(*x, *rf).unwrap());
+        for (i, value) in indicator.iter().enumerate().skip(3) {
+            let excess: Vec<f64> = (i - 3..=i).map(|j| XS[j] - risk_free[j]).collect();
+            assert_approx_eq!(
+                f64,
+                excess.iter().mean() / excess.iter().std_dev(),
+                *value.unwrap(),
+                epsilon = 0.0000001
+            );
+        }
+        let mut constant = SharpeRatio::new(10, 10.0, 0.05).unwrap();
+        let mut series = SharpeRatio::new(10, 10.0, 0.0).unwrap();
+        let risk_free = 1.05_f64.powf(0.1) - 1.0;
+        XS.iter().for_each(|x| {
+            constant.feed(*x).unwrap();
+            series.feed_with_risk_free(*x, risk_free).unwrap();
+        });
+        assert_approx_eq!(
+            f64,
+            *constant.last().unwrap(),
+            *series.last().unwrap(),
+            epsilon = 0.0000001
+        );
+    }
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Err(Error::ZeroWindow),
+            SharpeRatio::new(0, 252.0, 0.0).map(|_| ())
+        );
+        let mut indicator = SharpeRatio::new(2, 252.0, 0.0).unwrap();
+        assert_eq!(Err(Error::NonFinite), indicator.feed(f64::NAN));
+        assert_eq!(0, indicator.iter().count());
+        indicator.feed(0.01).unwrap();
+        assert_eq!(Err(Error::ZeroVariance), indicator.feed(0.01));
+        assert_eq!(None, indicator.last());
+        indicator.feed(0.02).unwrap();
+        assert!(indicator.last().is_some());
+    }
+    #[test]
+    fn rolls_into_flat_window() {
+        let mut indicator = SharpeRatio::new(3, 252.0, 0.0).unwrap();
+        for x in [0.037, -0.021, 0.013, 0.01, 0.01] {
+            indicator.feed(x).unwrap();
+        }
+        assert_eq!(Err(Error::ZeroVariance), indicator.feed(0.01));
+        assert_eq!(None, indicator.last());
+        assert_eq!(Err(Error::ZeroVariance), indicator.feed(0.01));
+    }
 }
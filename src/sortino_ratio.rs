@@ -1,71 +1,176 @@
-use statrs::statistics::Statistics;
-
-use crate::{downside_risk::DownsideRisk, Indicator, ReturnExt};
+use crate::{
+    downside_risk::DownsideRisk,
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    rolling::{RollingSum, Window},
+    Error, Indicator, ReturnExt,
+};
 
 #[derive(Debug)]
 pub struct SortinoRatio {
     pub freq: usize,
+    pub periods_per_year: f64,
     pub risk_free: f64,
     pub mar: f64,
-    input: Vec<f64>,
+    input: Window,
+    sum: RollingSum,
     downside_risk: DownsideRisk,
-    pub values: Vec<Option<f64>>,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
 }
 
 impl SortinoRatio {
-    pub fn new(freq: usize, risk_free: f64, mar: f64) -> Self {
-        Self {
+    pub fn new(
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: f64,
+        mar: f64,
+    ) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        if !(periods_per_year.is_finite() && periods_per_year > 0.0) {
+            return Err(Error::InvalidParameter);
+        }
+        Ok(Self {
             freq,
+            periods_per_year,
             risk_free,
             mar,
-            input: Vec::with_capacity(freq),
-            downside_risk: DownsideRisk::new(freq, mar),
-            values: Vec::with_capacity(freq),
+            input: Window::new(freq),
+            sum: RollingSum::default(),
+            downside_risk: DownsideRisk::new(freq, mar)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+
+    /// Feeds `ret` together with the risk-free rate for the same period,
+    /// ignoring the constant `risk_free`. Downside risk is still measured
+    /// against `mar`.
+    pub fn feed_with_risk_free(&mut self, ret: f64, risk_free: f64) -> Result<(), Error> {
+        let (Some(ret), Some(risk_free)) = (
+            self.nan_policy.apply(ret)?,
+            self.nan_policy.apply(risk_free)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.downside_risk.feed(ret)?;
+        let excess = ret - risk_free;
+        if let Some(old) = self.input.push(excess) {
+            self.sum.remove(old);
+        }
+        self.sum.add(excess);
+        match self.downside_risk.last() {
+            Some(&0.0) => {
+                self.values.push(None);
+                return Err(Error::ZeroVariance);
+            }
+            Some(&downside_risk) => {
+                let mean = self.sum.sum() / self.freq as f64;
+                self.values.push(Some(mean / downside_risk));
+            }
+            None => self.values.push(None),
         }
+        Ok(())
     }
 }
 
 impl Indicator for SortinoRatio {
     type Input = f64;
     type Output = f64;
-    fn feed(&mut self, ret: Self::Input) {
-        self.downside_risk.feed(ret);
-        self.input.push(ret);
-        if self.input.len() >= self.freq {
-            let downside_risk = self.downside_risk.last().unwrap();
-            let mean = self.input[self.input.len() - self.freq..].iter().mean();
-            self.values
-                .push(Some((mean - self.risk_free) / downside_risk));
-        } else {
-            self.values.push(None)
-        }
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let risk_free = (1.0 + self.risk_free).powf(1.0 / self.periods_per_year) - 1.0;
+        self.feed_with_risk_free(ret, risk_free)
     }
 
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
 pub trait SortinoRatioExt {
-    fn sortino_ratio(&self, freq: usize, risk_free: f64, mar: f64) -> Option<SortinoRatio>;
+    fn sortino_ratio(
+        &self,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: f64,
+        mar: f64,
+    ) -> Result<SortinoRatio, Error>;
+    fn sortino_ratio_with_risk_free(
+        &self,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: &[f64],
+        mar: f64,
+    ) -> Result<SortinoRatio, Error>;
 }
 
 impl<T> SortinoRatioExt for T
 where
     T: ReturnExt,
 {
-    fn sortino_ratio(&self, freq: usize, risk_free: f64, mar: f64) -> Option<SortinoRatio> {
-        let mut indicator = SortinoRatio::new(freq, risk_free, mar);
-        if let Some(ret) = self.ret() {
-            ret.iter().for_each(|&v| indicator.feed(v));
-            Some(indicator)
-        } else {
-            None
+    fn sortino_ratio(
+        &self,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: f64,
+        mar: f64,
+    ) -> Result<SortinoRatio, Error> {
+        feed_all(
+            SortinoRatio::new(freq, periods_per_year, risk_free, mar)?,
+            self.ret()?,
+        )
+    }
+
+    fn sortino_ratio_with_risk_free(
+        &self,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: &[f64],
+        mar: f64,
+    ) -> Result<SortinoRatio, Error> {
+        let ret = self.ret()?;
+        if ret.len() != risk_free.len() {
+            return Err(Error::LengthMismatch {
+                left: ret.len(),
+                right: risk_free.len(),
+            });
+        }
+        let mut indicator = SortinoRatio::new(freq, periods_per_year, 0.0, mar)?;
+        for (&ret, &risk_free) in ret.iter().zip(risk_free) {
+            match indicator.feed_with_risk_free(ret, risk_free) {
+                Ok(()) | Err(Error::ZeroVariance) => {}
+                Err(err) => return Err(err),
+            }
         }
+        Ok(indicator)
     }
 }
 
@@ -80,8 +185,8 @@ mod test {
     ];
     #[test]
     fn sortino_ratio() {
-        let mut indicator = SortinoRatio::new(10, 0.0, 0.0);
-        XS.iter().for_each(|x| indicator.feed(*x));
+        let mut indicator = SortinoRatio::new(10, 10.0, 0.0, 0.0).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
         assert_approx_eq!(
             f64,
             3.401051,
@@ -89,4 +194,20 @@ mod test {
             epsilon = 0.0000001
         );
     }
+    #[test]
+    fn risk_free_series() {
+        let risk_free: Vec<f64> = (0..XS.len()).map(|i| 0.001 * i as f64).collect();
+        let mut indicator = SortinoRatio::new(10, 10.0, 0.0, 0.0).unwrap();
+        XS.iter()
+            .zip(&risk_free)
+            .for_each(|(x, rf)| indicator.feed_with_risk_free(*x, *rf).unwrap());
+        let mean = XS.iter().zip(&risk_free).map(|(x, rf)| x - rf).sum::<f64>() / 10.0;
+        let downside_risk = (XS.iter().map(|x| x.min(0.0).powi(2)).sum::<f64>() / 10.0).sqrt();
+        assert_approx_eq!(
+            f64,
+            mean / downside_risk,
+            *indicator.last().unwrap(),
+            epsilon = 0.0000001
+        );
+    }
 }
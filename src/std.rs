@@ -1,21 +1,42 @@
-use statrs::statistics::Statistics;
-
-use crate::{Indicator, ReturnExt};
+use crate::{
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    rolling::{RollingMoments, Window},
+    Error, Indicator, ReturnExt,
+};
 
 #[derive(Debug)]
 pub struct Std {
     pub freq: usize,
-    input: Vec<f64>,
-    pub values: Vec<Option<f64>>,
+    input: Window,
+    moments: RollingMoments,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
 }
 
 impl Std {
-    pub fn new(freq: usize) -> Self {
-        Self {
-            freq,
-            input: Vec::with_capacity(freq),
-            values: Vec::with_capacity(freq),
+    pub fn new(freq: usize) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
         }
+        Ok(Self {
+            freq,
+            input: Window::new(freq),
+            moments: RollingMoments::default(),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
     }
 }
 
@@ -23,40 +44,74 @@ impl Indicator for Std {
     type Input = f64;
     type Output = f64;
 
-    fn feed(&mut self, ret: Self::Input) {
-        self.input.push(ret);
-        if self.input.len() >= self.freq {
-            let value = self.input[self.input.len() - self.freq..].iter().std_dev();
-            self.values.push(Some(value));
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        if let Some(old) = self.input.push(ret) {
+            self.moments.remove(old);
+        }
+        self.moments.add(ret);
+        if self.input.is_full() {
+            self.values.push(Some(self.moments.std_dev()));
         } else {
             self.values.push(None)
         }
+        Ok(())
     }
 
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
 pub trait StdExt {
-    fn std(&self, freq: usize) -> Option<Std>;
+    fn std(&self, freq: usize) -> Result<Std, Error>;
 }
 
 impl<T> StdExt for T
 where
     T: ReturnExt,
 {
-    fn std(&self, freq: usize) -> Option<Std> {
-        if let Some(ret) = self.ret() {
-            let mut indicator = Std::new(freq);
-            ret.iter().for_each(|&v| indicator.feed(v));
-            Some(indicator)
-        } else {
-            None
+    fn std(&self, freq: usize) -> Result<Std, Error> {
+        feed_all(Std::new(freq)?, self.ret()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+    use statrs::statistics::Statistics;
+
+    use crate::{std::Std, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+    #[test]
+    fn rolling_matches_batch() {
+        let mut indicator = Std::new(4).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        for (i, value) in indicator.iter().enumerate().skip(3) {
+            assert_approx_eq!(
+                f64,
+                XS[i - 3..=i].iter().std_dev(),
+                *value.unwrap(),
+                epsilon = 0.0000001
+            );
         }
     }
 }
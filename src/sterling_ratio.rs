@@ -0,0 +1,170 @@
+use crate::{
+    annualized_return::AnnualizedReturn,
+    feed_all,
+    history::{History, Values},
+    maximum_drawdown::MaximumDrawdown,
+    mode,
+    nan_policy::NanPolicy,
+    Error, Indicator, ReturnExt,
+};
+
+/// Annualized return over the maximum drawdown plus a non-negative `excess`,
+/// customarily `0.1`.
+#[derive(Debug)]
+pub struct SterlingRatio {
+    pub freq: usize,
+    pub periods_per_year: f64,
+    pub excess: f64,
+    annualized_return: AnnualizedReturn<mode::Geometric>,
+    maximum_drawdown: MaximumDrawdown,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl SterlingRatio {
+    pub fn new(freq: usize, periods_per_year: f64, excess: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        if !(excess.is_finite() && excess >= 0.0) {
+            return Err(Error::InvalidParameter);
+        }
+        Ok(Self {
+            freq,
+            periods_per_year,
+            excess,
+            annualized_return: AnnualizedReturn::new(mode::Geometric, freq, periods_per_year)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            maximum_drawdown: MaximumDrawdown::new(freq)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for SterlingRatio {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.annualized_return.feed(ret)?;
+        self.maximum_drawdown.feed(ret)?;
+        if let (Some(annret), Some(max_drawdown)) =
+            (self.annualized_return.last(), self.maximum_drawdown.last())
+        {
+            let denominator = max_drawdown + self.excess;
+            if denominator == 0.0 {
+                self.values.push(None);
+                return Err(Error::ZeroVariance);
+            }
+            self.values.push(Some(annret / denominator));
+        } else {
+            self.values.push(None)
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait SterlingRatioExt {
+    fn sterling_ratio(
+        &self,
+        freq: usize,
+        periods_per_year: f64,
+        excess: f64,
+    ) -> Result<SterlingRatio, Error>;
+}
+
+impl<T> SterlingRatioExt for T
+where
+    T: ReturnExt,
+{
+    fn sterling_ratio(
+        &self,
+        freq: usize,
+        periods_per_year: f64,
+        excess: f64,
+    ) -> Result<SterlingRatio, Error> {
+        feed_all(
+            SterlingRatio::new(freq, periods_per_year, excess)?,
+            self.ret()?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::SterlingRatio;
+    use crate::{Error, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn sterling_ratio() {
+        let mut indicator = SterlingRatio::new(10, 10.0, 0.1).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.19135615147149543 / 0.114,
+            *indicator.last().unwrap(),
+            epsilon = 0.0000001
+        );
+    }
+
+    #[test]
+    fn no_drawdown() {
+        let mut indicator = SterlingRatio::new(2, 10.0, 0.1).unwrap();
+        indicator.feed(0.01).unwrap();
+        indicator.feed(0.02).unwrap();
+        assert!(indicator.last().is_some());
+        let mut indicator = SterlingRatio::new(2, 10.0, 0.0).unwrap();
+        indicator.feed(0.01).unwrap();
+        assert_eq!(Err(Error::ZeroVariance), indicator.feed(0.02));
+    }
+
+    #[test]
+    fn invalid_excess() {
+        for excess in [-0.1, f64::NAN, f64::INFINITY] {
+            assert_eq!(
+                Err(Error::InvalidParameter),
+                SterlingRatio::new(10, 10.0, excess).map(|_| ())
+            );
+        }
+    }
+}
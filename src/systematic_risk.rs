@@ -0,0 +1,182 @@
+use erfurt::candle::CandlesExt;
+
+use crate::{
+    aligned_ret, feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    rolling::{RollingCovariance, Window},
+    Error, Indicator,
+};
+
+/// Annualized volatility of the portfolio explained by the benchmark,
+/// `|beta| * benchmark volatility`, fed as `(portfolio, benchmark)`.
+#[derive(Debug)]
+pub struct SystematicRisk {
+    pub freq: usize,
+    pub periods_per_year: f64,
+    portfolio: Window,
+    benchmark: Window,
+    covariance: RollingCovariance,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl SystematicRisk {
+    pub fn new(freq: usize, periods_per_year: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        if !(periods_per_year.is_finite() && periods_per_year > 0.0) {
+            return Err(Error::InvalidParameter);
+        }
+        Ok(Self {
+            freq,
+            periods_per_year,
+            portfolio: Window::new(freq),
+            benchmark: Window::new(freq),
+            covariance: RollingCovariance::default(),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for SystematicRisk {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        if let (Some(old), Some(old_benchmark)) = (
+            self.portfolio.push(portfolio),
+            self.benchmark.push(benchmark),
+        ) {
+            self.covariance.remove(old, old_benchmark);
+        }
+        self.covariance.add(portfolio, benchmark);
+        if self.portfolio.is_full() {
+            let variance = self.covariance.variance_y();
+            if variance == 0.0 {
+                self.values.push(None);
+                return Err(Error::ZeroVariance);
+            }
+            let value =
+                (self.covariance.covariance().powi(2) / variance * self.periods_per_year).sqrt();
+            self.values.push(Some(value));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait SystematicRiskExt {
+    fn systematic_risk<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+    ) -> Result<SystematicRisk, Error>;
+}
+
+impl<T> SystematicRiskExt for T
+where
+    T: CandlesExt,
+{
+    fn systematic_risk<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+    ) -> Result<SystematicRisk, Error> {
+        feed_all(
+            SystematicRisk::new(freq, periods_per_year)?,
+            aligned_ret(self, benchmark)?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::SystematicRisk;
+    use crate::{Error, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+    static YS: [f64; 10] = [
+        -0.005, 0.081, 0.04, -0.037, -0.061, 0.058, -0.049, -0.021, 0.062, 0.058,
+    ];
+
+    #[test]
+    fn systematic_risk() {
+        let mut indicator = SystematicRisk::new(10, 10.0).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.003856110755503518,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn flat_benchmark() {
+        let mut indicator = SystematicRisk::new(2, 10.0).unwrap();
+        indicator.feed((0.01, 0.02)).unwrap();
+        assert_eq!(Err(Error::ZeroVariance), indicator.feed((0.03, 0.02)));
+        assert_eq!(None, indicator.last());
+    }
+
+    #[test]
+    fn rolls_into_flat_benchmark() {
+        let mut indicator = SystematicRisk::new(3, 10.0).unwrap();
+        for x in [
+            (0.02, 0.037),
+            (0.01, -0.021),
+            (-0.03, 0.013),
+            (0.04, 0.01),
+            (0.01, 0.01),
+        ] {
+            indicator.feed(x).unwrap();
+        }
+        assert_eq!(Err(Error::ZeroVariance), indicator.feed((0.03, 0.01)));
+        assert_eq!(None, indicator.last());
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{
+    drawdown_episode::drawdown_episodes,
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    rolling::Window,
+    Error, Indicator, ReturnExt,
+};
+
+/// Periods the window has spent below its high-water mark since the last
+/// peak, zero at a new high. Each feed rebuilds the window's drawdown
+/// episodes, so an update costs `O(freq)`.
+#[derive(Debug)]
+pub struct TimeUnderWater {
+    pub freq: usize,
+    input: Window,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl TimeUnderWater {
+    pub fn new(freq: usize) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            input: Window::new(freq),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for TimeUnderWater {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.input.push(ret);
+        if self.input.is_full() && !self.input.is_finite() {
+            self.values.push(Some(f64::NAN));
+        } else if self.input.is_full() {
+            let xs: Vec<f64> = self.input.iter().copied().collect();
+            let value = match drawdown_episodes(&xs)?.last() {
+                Some(episode) if episode.end.is_none() => episode.length as f64,
+                _ => 0.0,
+            };
+            self.values.push(Some(value));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait TimeUnderWaterExt {
+    fn time_under_water(&self, freq: usize) -> Result<TimeUnderWater, Error>;
+}
+
+impl<T> TimeUnderWaterExt for T
+where
+    T: ReturnExt,
+{
+    fn time_under_water(&self, freq: usize) -> Result<TimeUnderWater, Error> {
+        feed_all(TimeUnderWater::new(freq)?, self.ret()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TimeUnderWater;
+    use crate::Indicator;
+
+    #[test]
+    fn time_under_water() {
+        let mut indicator = TimeUnderWater::new(4).unwrap();
+        [0.01, -0.02, 0.01, 0.005, 0.02, 0.01, -0.01]
+            .iter()
+            .for_each(|x| indicator.feed(*x).unwrap());
+        let values: Vec<_> = indicator.iter().map(|x| x.copied()).collect();
+        assert_eq!(
+            vec![None, None, None, Some(3.0), Some(0.0), Some(0.0), Some(1.0)],
+            values
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use chrono::{DateTime, Duration, Utc};
+use erfurt::candle::CandlesExt;
+
+use crate::{
+    align,
+    drawdown_episode::{drawdown_episodes, DrawdownEpisode},
+    Error, Indicator, ReturnExt,
+};
+
+pub type Timed<T> = Vec<(DateTime<Utc>, T)>;
+
+/// Timestamp-aware counterparts of [`ReturnExt`] and the `*Ext` helpers.
+///
+/// Returns are computed one per candle, so an indicator built from them with
+/// any `*Ext` helper lines up index by index with `CandlesExt::time()`.
+///
+/// The `wall_clock_*` methods read `None` for a window holding a non-finite
+/// return, where their index-based indicators report NaN.
+pub trait TimedExt {
+    fn timed_ret(&self) -> Result<Timed<f64>, Error>;
+    fn timed<T>(&self, indicator: &T) -> Result<Timed<Option<T::Output>>, Error>
+    where
+        T: Indicator,
+        T::Output: Clone;
+    /// Runs any single-series `*Ext` helper and timestamps its outputs, e.g.
+    /// `candles.timed_ext(|c| c.sharpe_ratio(20, 252.0, 0.0))`.
+    fn timed_ext<T, F>(&self, f: F) -> Result<Timed<Option<T::Output>>, Error>
+    where
+        F: FnOnce(&Self) -> Result<T, Error>,
+        T: Indicator,
+        T::Output: Clone;
+    /// Runs any benchmark-relative `*Ext` helper and timestamps its outputs
+    /// with the times both series share, e.g.
+    /// `candles.timed_benchmark_ext(&index, |c, b| c.beta(b, 20))`.
+    fn timed_benchmark_ext<B, T, F>(
+        &self,
+        benchmark: &B,
+        f: F,
+    ) -> Result<Timed<Option<T::Output>>, Error>
+    where
+        B: CandlesExt,
+        F: FnOnce(&Self, &B) -> Result<T, Error>,
+        T: Indicator,
+        T::Output: Clone;
+    /// Wall-clock counterpart of [`TimeUnderWater`](crate::time_under_water::TimeUnderWater).
+    fn wall_clock_time_under_water(&self, freq: usize) -> Result<Timed<Option<Duration>>, Error>;
+    /// Wall-clock counterpart of
+    /// [`MaxDrawdownDuration`](crate::max_drawdown_duration::MaxDrawdownDuration).
+    fn wall_clock_max_drawdown_duration(
+        &self,
+        freq: usize,
+    ) -> Result<Timed<Option<Duration>>, Error>;
+    /// Wall-clock counterpart of
+    /// [`AverageRecoveryTime`](crate::average_recovery_time::AverageRecoveryTime).
+    fn wall_clock_average_recovery_time(
+        &self,
+        freq: usize,
+    ) -> Result<Timed<Option<Duration>>, Error>;
+}
+
+impl<C> TimedExt for C
+where
+    C: CandlesExt,
+{
+    fn timed_ret(&self) -> Result<Timed<f64>, Error> {
+        let ret = self.ret()?;
+        Ok(self.time().iter().copied().zip(ret).collect())
+    }
+
+    fn timed<T>(&self, indicator: &T) -> Result<Timed<Option<T::Output>>, Error>
+    where
+        T: Indicator,
+        T::Output: Clone,
+    {
+        with_time(self.time(), indicator)
+    }
+
+    fn timed_ext<T, F>(&self, f: F) -> Result<Timed<Option<T::Output>>, Error>
+    where
+        F: FnOnce(&Self) -> Result<T, Error>,
+        T: Indicator,
+        T::Output: Clone,
+    {
+        with_time(self.time(), &f(self)?)
+    }
+
+    fn timed_benchmark_ext<B, T, F>(
+        &self,
+        benchmark: &B,
+        f: F,
+    ) -> Result<Timed<Option<T::Output>>, Error>
+    where
+        B: CandlesExt,
+        F: FnOnce(&Self, &B) -> Result<T, Error>,
+        T: Indicator,
+        T::Output: Clone,
+    {
+        let time: Vec<_> = align(
+            self.time(),
+            self.close(),
+            benchmark.time(),
+            benchmark.close(),
+        )?
+        .into_iter()
+        .map(|(t, _)| t)
+        .collect();
+        with_time(&time, &f(self, benchmark)?)
+    }
+
+    fn wall_clock_time_under_water(&self, freq: usize) -> Result<Timed<Option<Duration>>, Error> {
+        rolling_episodes(self.time(), &self.ret()?, freq, time_under_water)
+    }
+
+    fn wall_clock_max_drawdown_duration(
+        &self,
+        freq: usize,
+    ) -> Result<Timed<Option<Duration>>, Error> {
+        rolling_episodes(self.time(), &self.ret()?, freq, max_drawdown_duration)
+    }
+
+    fn wall_clock_average_recovery_time(
+        &self,
+        freq: usize,
+    ) -> Result<Timed<Option<Duration>>, Error> {
+        rolling_episodes(self.time(), &self.ret()?, freq, average_recovery_time)
+    }
+}
+
+fn time_under_water(episodes: &[DrawdownEpisode], time: &[DateTime<Utc>]) -> Option<Duration> {
+    match episodes.last() {
+        Some(episode) if episode.end.is_none() => episode.duration(time),
+        _ => Some(Duration::zero()),
+    }
+}
+
+fn max_drawdown_duration(episodes: &[DrawdownEpisode], time: &[DateTime<Utc>]) -> Option<Duration> {
+    let durations = episodes
+        .iter()
+        .map(|episode| episode.duration(time))
+        .collect::<Option<Vec<_>>>()?;
+    Some(durations.into_iter().max().unwrap_or_else(Duration::zero))
+}
+
+fn average_recovery_time(episodes: &[DrawdownEpisode], time: &[DateTime<Utc>]) -> Option<Duration> {
+    let recoveries: Vec<Duration> = episodes
+        .iter()
+        .filter_map(|episode| episode.recovery_duration(time))
+        .collect();
+    if recoveries.is_empty() {
+        return None;
+    }
+    let total = recoveries.iter().fold(Duration::zero(), |acc, &x| acc + x);
+    Some(total / recoveries.len() as i32)
+}
+
+/// Applies `f` to the drawdown episodes of every full window of `ret`,
+/// together with the timestamps of that window. Windows after the first are
+/// prefixed with a zero return at the preceding timestamp, so an episode
+/// starting at the front of the window is timed from its peak.
+fn rolling_episodes<F>(
+    time: &[DateTime<Utc>],
+    ret: &[f64],
+    freq: usize,
+    f: F,
+) -> Result<Timed<Option<Duration>>, Error>
+where
+    F: Fn(&[DrawdownEpisode], &[DateTime<Utc>]) -> Option<Duration>,
+{
+    if freq == 0 {
+        return Err(Error::ZeroWindow);
+    }
+    if time.len() != ret.len() {
+        return Err(Error::LengthMismatch {
+            left: time.len(),
+            right: ret.len(),
+        });
+    }
+    let mut timed = Vec::with_capacity(ret.len());
+    for (i, &t) in time.iter().enumerate() {
+        let value = if i + 1 < freq {
+            None
+        } else {
+            let start = i + 1 - freq;
+            let (window_ret, window_time) = if start > 0 {
+                let window_ret: Vec<f64> = std::iter::once(0.0)
+                    .chain(ret[start..=i].iter().copied())
+                    .collect();
+                (window_ret, &time[start - 1..=i])
+            } else {
+                (ret[..=i].to_vec(), &time[..=i])
+            };
+            match drawdown_episodes(&window_ret) {
+                Ok(episodes) => f(&episodes, window_time),
+                Err(Error::NonFinite) => None,
+                Err(err) => return Err(err),
+            }
+        };
+        timed.push((t, value));
+    }
+    Ok(timed)
+}
+
+/// Pairs the `i`-th output of `indicator` with `time[i]`.
+///
+/// Outputs dropped by a bounded [`History`](crate::history::History) come
+/// back as `None`.
+pub fn with_time<T>(
+    time: &[DateTime<Utc>],
+    indicator: &T,
+) -> Result<Timed<Option<T::Output>>, Error>
+where
+    T: Indicator,
+    T::Output: Clone,
+{
+    if time.len() != indicator.len() {
+        return Err(Error::LengthMismatch {
+            left: time.len(),
+            right: indicator.len(),
+        });
+    }
+    Ok(time
+        .iter()
+        .enumerate()
+        .map(|(i, &t)| (t, indicator.get(i).cloned()))
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+
+    use crate::{
+        align, drawdown_episode::DrawdownEpisode, history::History, std::Std, Error, Indicator,
+    };
+
+    use super::{
+        average_recovery_time, max_drawdown_duration, rolling_episodes, time_under_water, with_time,
+    };
+
+    static XS: [f64; 5] = [0.003, 0.026, 0.015, -0.009, 0.014];
+
+    #[test]
+    fn with_time_aligns() {
+        let time: Vec<_> = (0..XS.len() as i64)
+            .map(|i| Utc.timestamp_opt(i * 86_400, 0).unwrap())
+            .collect();
+        let mut indicator = Std::new(3).unwrap().with_history(History::Last(2));
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        let timed = with_time(&time, &indicator).unwrap();
+        assert_eq!(XS.len(), timed.len());
+        assert_eq!(time[4], timed[4].0);
+        assert_eq!(indicator.last().copied(), timed[4].1);
+        assert_eq!(None, timed[2].1);
+        assert_eq!(
+            Err(Error::LengthMismatch { left: 4, right: 5 }),
+            with_time(&time[1..], &indicator)
+        );
+    }
+
+    #[test]
+    fn wall_clock_durations() {
+        let ret = [0.0, 0.01, -0.02, 0.01, 0.005, 0.02, 0.01, -0.01];
+        let time: Vec<_> = [0, 1, 2, 3, 6, 7, 8, 9]
+            .iter()
+            .map(|&d| Utc.timestamp_opt(d * 86_400, 0).unwrap())
+            .collect();
+        type Metric = fn(&[DrawdownEpisode], &[DateTime<Utc>]) -> Option<Duration>;
+        let days = |f: Metric| -> Vec<Option<i64>> {
+            rolling_episodes(&time, &ret, 5, f)
+                .unwrap()
+                .into_iter()
+                .map(|(_, x)| x.map(|d| d.num_days()))
+                .collect()
+        };
+        let none = [None; 4];
+        assert_eq!(
+            [&none[..], &[Some(5), Some(0), Some(0), Some(1)]].concat(),
+            days(time_under_water)
+        );
+        assert_eq!(
+            [&none[..], &[Some(5), Some(6), Some(6), Some(1)]].concat(),
+            days(max_drawdown_duration)
+        );
+        assert_eq!(
+            [&none[..], &[None, Some(5), Some(5), None]].concat(),
+            days(average_recovery_time)
+        );
+    }
+
+    #[test]
+    fn align_keeps_shared_times() {
+        let day = |d: i64| Utc.timestamp_opt(d * 86_400, 0).unwrap();
+        let first_time = [day(0), day(1), day(2), day(4)];
+        let second_time = [day(1), day(2), day(3), day(4), day(5)];
+        let aligned = align(
+            &first_time,
+            &[1.0, 2.0, 3.0, 4.0],
+            &second_time,
+            &[10.0, 20.0, 30.0, 40.0, 50.0],
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                (day(1), (2.0, 10.0)),
+                (day(2), (3.0, 20.0)),
+                (day(4), (4.0, 40.0)),
+            ],
+            aligned
+        );
+        assert_eq!(
+            Err(Error::EmptyInput),
+            align(&first_time[..1], &[1.0], &second_time, &[1.0; 5])
+        );
+    }
+
+    #[test]
+    fn wall_clock_non_finite_window() {
+        let ret = [0.0, -0.01, f64::NAN, 0.02];
+        let time: Vec<_> = (0..4)
+            .map(|d| Utc.timestamp_opt(d * 86_400, 0).unwrap())
+            .collect();
+        let timed = rolling_episodes(&time, &ret, 2, time_under_water).unwrap();
+        let durations: Vec<_> = timed.into_iter().map(|(_, x)| x).collect();
+        assert_eq!(vec![None, Some(Duration::days(1)), None, None], durations);
+    }
+}
@@ -0,0 +1,154 @@
+use erfurt::candle::CandlesExt;
+
+use crate::{
+    aligned_ret, feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    rolling::{RollingCovariance, Window},
+    Error, Indicator,
+};
+
+/// Annualized portfolio volatility, the root of the summed squares of
+/// [`SystematicRisk`](crate::systematic_risk::SystematicRisk) and
+/// [`SpecificRisk`](crate::specific_risk::SpecificRisk). Fed as
+/// `(portfolio, benchmark)`.
+#[derive(Debug)]
+pub struct TotalRisk {
+    pub freq: usize,
+    pub periods_per_year: f64,
+    portfolio: Window,
+    benchmark: Window,
+    covariance: RollingCovariance,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl TotalRisk {
+    pub fn new(freq: usize, periods_per_year: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        if !(periods_per_year.is_finite() && periods_per_year > 0.0) {
+            return Err(Error::InvalidParameter);
+        }
+        Ok(Self {
+            freq,
+            periods_per_year,
+            portfolio: Window::new(freq),
+            benchmark: Window::new(freq),
+            covariance: RollingCovariance::default(),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for TotalRisk {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        if let (Some(old), Some(old_benchmark)) = (
+            self.portfolio.push(portfolio),
+            self.benchmark.push(benchmark),
+        ) {
+            self.covariance.remove(old, old_benchmark);
+        }
+        self.covariance.add(portfolio, benchmark);
+        if self.portfolio.is_full() {
+            let value = (self.covariance.variance_x() * self.periods_per_year).sqrt();
+            self.values.push(Some(value));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait TotalRiskExt {
+    fn total_risk<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+    ) -> Result<TotalRisk, Error>;
+}
+
+impl<T> TotalRiskExt for T
+where
+    T: CandlesExt,
+{
+    fn total_risk<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+    ) -> Result<TotalRisk, Error> {
+        feed_all(
+            TotalRisk::new(freq, periods_per_year)?,
+            aligned_ret(self, benchmark)?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::TotalRisk;
+    use crate::Indicator;
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+    static YS: [f64; 10] = [
+        -0.005, 0.081, 0.04, -0.037, -0.061, 0.058, -0.049, -0.021, 0.062, 0.058,
+    ];
+
+    #[test]
+    fn total_risk() {
+        let mut indicator = TotalRisk::new(10, 10.0).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.07346125206907078,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+}
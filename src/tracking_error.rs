@@ -0,0 +1,148 @@
+use erfurt::candle::CandlesExt;
+
+use crate::{
+    aligned_ret, feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    rolling::{RollingMoments, Window},
+    Error, Indicator,
+};
+
+/// Annualized standard deviation of portfolio minus benchmark returns, fed as
+/// `(portfolio, benchmark)`.
+#[derive(Debug)]
+pub struct TrackingError {
+    pub freq: usize,
+    pub periods_per_year: f64,
+    input: Window,
+    moments: RollingMoments,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl TrackingError {
+    pub fn new(freq: usize, periods_per_year: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        if !(periods_per_year.is_finite() && periods_per_year > 0.0) {
+            return Err(Error::InvalidParameter);
+        }
+        Ok(Self {
+            freq,
+            periods_per_year,
+            input: Window::new(freq),
+            moments: RollingMoments::default(),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for TrackingError {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        let active = portfolio - benchmark;
+        if let Some(old) = self.input.push(active) {
+            self.moments.remove(old);
+        }
+        self.moments.add(active);
+        if self.input.is_full() {
+            let value = self.moments.std_dev() * self.periods_per_year.sqrt();
+            self.values.push(Some(value));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait TrackingErrorExt {
+    fn tracking_error<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+    ) -> Result<TrackingError, Error>;
+}
+
+impl<T> TrackingErrorExt for T
+where
+    T: CandlesExt,
+{
+    fn tracking_error<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+    ) -> Result<TrackingError, Error> {
+        feed_all(
+            TrackingError::new(freq, periods_per_year)?,
+            aligned_ret(self, benchmark)?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::TrackingError;
+    use crate::Indicator;
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+    static YS: [f64; 10] = [
+        -0.005, 0.081, 0.04, -0.037, -0.061, 0.058, -0.049, -0.021, 0.062, 0.058,
+    ];
+
+    #[test]
+    fn tracking_error() {
+        let mut indicator = TrackingError::new(10, 10.0).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.17888574876471047,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use erfurt::candle::CandlesExt;
+
+use crate::{
+    aligned_ret,
+    annualized_return::AnnualizedReturn,
+    beta::Beta,
+    feed_all,
+    history::{History, Values},
+    mode,
+    nan_policy::NanPolicy,
+    Error, Indicator,
+};
+
+/// Annualized portfolio return in excess of `risk_free`, an annual rate, per
+/// unit of beta. Fed as `(portfolio, benchmark)`.
+#[derive(Debug)]
+pub struct TreynorRatio {
+    pub freq: usize,
+    pub periods_per_year: f64,
+    pub risk_free: f64,
+    portfolio_return: AnnualizedReturn<mode::Geometric>,
+    beta: Beta,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl TreynorRatio {
+    pub fn new(freq: usize, periods_per_year: f64, risk_free: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            periods_per_year,
+            risk_free,
+            portfolio_return: AnnualizedReturn::new(mode::Geometric, freq, periods_per_year)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            beta: Beta::new(freq)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for TreynorRatio {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.portfolio_return.feed(portfolio)?;
+        if let Err(err) = self.beta.feed((portfolio, benchmark)) {
+            self.values.push(None);
+            return Err(err);
+        }
+        match (self.portfolio_return.last(), self.beta.last()) {
+            (Some(_), Some(&0.0)) => {
+                self.values.push(None);
+                return Err(Error::ZeroVariance);
+            }
+            (Some(rp), Some(beta)) => self.values.push(Some((rp - self.risk_free) / beta)),
+            _ => self.values.push(None),
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait TreynorRatioExt {
+    fn treynor_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: f64,
+    ) -> Result<TreynorRatio, Error>;
+}
+
+impl<T> TreynorRatioExt for T
+where
+    T: CandlesExt,
+{
+    fn treynor_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        freq: usize,
+        periods_per_year: f64,
+        risk_free: f64,
+    ) -> Result<TreynorRatio, Error> {
+        feed_all(
+            TreynorRatio::new(freq, periods_per_year, risk_free)?,
+            aligned_ret(self, benchmark)?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::TreynorRatio;
+    use crate::Indicator;
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+    static YS: [f64; 10] = [
+        -0.005, 0.081, 0.04, -0.037, -0.061, 0.058, -0.049, -0.021, 0.062, 0.058,
+    ];
+
+    #[test]
+    fn treynor_ratio() {
+        let mut indicator = TreynorRatio::new(10, 10.0, 0.02).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(
+            f64,
+            7.421412451169891,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    Error, Indicator, ReturnExt,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Underwater {
+    /// Growth of one unit invested before the first return.
+    pub wealth: f64,
+    pub high_water_mark: f64,
+    /// Loss from the high-water mark, as a positive fraction.
+    pub drawdown: f64,
+}
+
+/// Running wealth, high-water mark and drawdown from the first input on,
+/// one point per input.
+#[derive(Debug)]
+pub struct UnderwaterCurve {
+    wealth: f64,
+    high_water_mark: f64,
+    pub nan_policy: NanPolicy,
+    pub values: Values<Underwater>,
+}
+
+impl Default for UnderwaterCurve {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UnderwaterCurve {
+    pub fn new() -> Self {
+        Self {
+            wealth: 1.0,
+            high_water_mark: 1.0,
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(0),
+        }
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for UnderwaterCurve {
+    type Input = f64;
+    type Output = Underwater;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.wealth *= 1.0 + ret;
+        if self.wealth > self.high_water_mark || self.wealth.is_nan() {
+            self.high_water_mark = self.wealth;
+        }
+        self.values.push(Some(Underwater {
+            wealth: self.wealth,
+            high_water_mark: self.high_water_mark,
+            drawdown: 1.0 - self.wealth / self.high_water_mark,
+        }));
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait UnderwaterCurveExt {
+    fn underwater_curve(&self) -> Result<UnderwaterCurve, Error>;
+}
+
+impl<T> UnderwaterCurveExt for T
+where
+    T: ReturnExt,
+{
+    fn underwater_curve(&self) -> Result<UnderwaterCurve, Error> {
+        feed_all(UnderwaterCurve::new(), self.ret()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::UnderwaterCurve;
+    use crate::{drawndown::drawdown_path, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn underwater_curve() {
+        let mut indicator = UnderwaterCurve::new();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        assert_eq!(XS.len(), indicator.len());
+        for (point, dd) in indicator.iter().zip(drawdown_path(XS.iter())) {
+            assert_approx_eq!(f64, dd, point.unwrap().drawdown, epsilon = 1e-12);
+        }
+        let point = indicator.get(3).unwrap();
+        assert_approx_eq!(
+            f64,
+            1.003 * 1.026 * 1.015,
+            point.high_water_mark,
+            epsilon = 1e-12
+        );
+        assert_approx_eq!(
+            f64,
+            point.high_water_mark * 0.991,
+            point.wealth,
+            epsilon = 1e-12
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use erfurt::candle::CandlesExt;
+
+use crate::{
+    aligned_ret, feed_all,
+    history::{History, Values},
+    mode,
+    nan_policy::NanPolicy,
+    paired::{Capture, PairedWindow},
+    Error, Indicator,
+};
+
+/// Portfolio return over benchmark return across the periods in which the
+/// benchmark rose, fed as `(portfolio, benchmark)`. `mode::Simple` compares
+/// arithmetic means, `mode::Geometric` compounded mean returns. A window
+/// without such a period, or whose compounded mean is undefined, is rejected
+/// with [`Error::Undefined`].
+#[derive(Debug)]
+pub struct UpCaptureRatio<T> {
+    pub mode: T,
+    pub freq: usize,
+    window: PairedWindow,
+    capture: Capture,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl<T> UpCaptureRatio<T> {
+    pub fn new(mode: T, freq: usize) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            mode,
+            freq,
+            window: PairedWindow::new(freq),
+            capture: Capture::new(|b| b > 0.0),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for UpCaptureRatio<mode::Geometric> {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.window.feed(
+            &mut self.capture,
+            &mut self.values,
+            (portfolio, benchmark),
+            Capture::geometric,
+        )
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+impl Indicator for UpCaptureRatio<mode::Simple> {
+    type Input = (f64, f64);
+    type Output = f64;
+
+    fn feed(&mut self, (portfolio, benchmark): Self::Input) -> Result<(), Error> {
+        let (Some(portfolio), Some(benchmark)) = (
+            self.nan_policy.apply(portfolio)?,
+            self.nan_policy.apply(benchmark)?,
+        ) else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.window.feed(
+            &mut self.capture,
+            &mut self.values,
+            (portfolio, benchmark),
+            Capture::simple,
+        )
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait UpCaptureRatioExt<T> {
+    fn up_capture_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        mode: T,
+        freq: usize,
+    ) -> Result<UpCaptureRatio<T>, Error>;
+}
+
+impl<T> UpCaptureRatioExt<mode::Geometric> for T
+where
+    T: CandlesExt,
+{
+    fn up_capture_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        mode: mode::Geometric,
+        freq: usize,
+    ) -> Result<UpCaptureRatio<mode::Geometric>, Error> {
+        feed_all(
+            UpCaptureRatio::new(mode, freq)?,
+            aligned_ret(self, benchmark)?,
+        )
+    }
+}
+
+impl<T> UpCaptureRatioExt<mode::Simple> for T
+where
+    T: CandlesExt,
+{
+    fn up_capture_ratio<B: CandlesExt>(
+        &self,
+        benchmark: &B,
+        mode: mode::Simple,
+        freq: usize,
+    ) -> Result<UpCaptureRatio<mode::Simple>, Error> {
+        feed_all(
+            UpCaptureRatio::new(mode, freq)?,
+            aligned_ret(self, benchmark)?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::UpCaptureRatio;
+    use crate::{mode, Error, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+    static YS: [f64; 10] = [
+        -0.005, 0.081, 0.04, -0.037, -0.061, 0.058, -0.049, -0.021, 0.062, 0.058,
+    ];
+
+    #[test]
+    fn simple() {
+        let mut indicator = UpCaptureRatio::new(mode::Simple, 10).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.3010033444816054,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn geometric() {
+        let mut indicator = UpCaptureRatio::new(mode::Geometric, 10).unwrap();
+        XS.iter()
+            .zip(YS.iter())
+            .for_each(|(x, y)| indicator.feed((*x, *y)).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.298794034654326,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn no_up_period() {
+        let mut indicator = UpCaptureRatio::new(mode::Simple, 2).unwrap();
+        indicator.feed((0.01, -0.01)).unwrap();
+        assert_eq!(Err(Error::Undefined), indicator.feed((0.02, 0.0)));
+        assert_eq!(None, indicator.last());
+    }
+
+    #[test]
+    fn rolling() {
+        let mut simple = UpCaptureRatio::new(mode::Simple, 2).unwrap();
+        let mut geometric = UpCaptureRatio::new(mode::Geometric, 2).unwrap();
+        for x in [(0.02, 0.01), (0.03, 0.02), (0.01, -0.01), (0.04, 0.02)] {
+            simple.feed(x).unwrap();
+            geometric.feed(x).unwrap();
+        }
+        let simple: Vec<_> = simple.iter().map(|x| x.copied()).collect();
+        assert_eq!(None, simple[0]);
+        assert_approx_eq!(f64, 0.05 / 0.03, simple[1].unwrap(), epsilon = 1e-12);
+        assert_approx_eq!(f64, 1.5, simple[2].unwrap(), epsilon = 1e-12);
+        assert_approx_eq!(f64, 2.0, simple[3].unwrap(), epsilon = 1e-12);
+        assert_approx_eq!(
+            f64,
+            ((1.02_f64 * 1.03).sqrt() - 1.0) / ((1.01_f64 * 1.02).sqrt() - 1.0),
+            *geometric.get(1).unwrap(),
+            epsilon = 1e-12
+        );
+        assert_approx_eq!(f64, 2.0, *geometric.last().unwrap(), epsilon = 1e-12);
+    }
+}
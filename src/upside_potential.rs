@@ -1,21 +1,44 @@
-use crate::{Indicator, ReturnExt};
+use crate::{
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    upper_partial_moment::UpperPartialMoment,
+    Error, Indicator, ReturnExt,
+};
 
 #[derive(Debug)]
 pub struct UpsidePotential {
     pub freq: usize,
     pub mar: f64,
-    input: Vec<f64>,
-    pub values: Vec<Option<f64>>,
+    upper_partial_moment: UpperPartialMoment,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
 }
 
 impl UpsidePotential {
-    pub fn new(freq: usize, mar: f64) -> Self {
-        Self {
+    pub fn new(freq: usize, mar: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
             freq,
             mar,
-            input: Vec::new(),
-            values: Vec::new(),
-        }
+            upper_partial_moment: UpperPartialMoment::new(freq, mar, 1)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
     }
 }
 
@@ -23,45 +46,43 @@ impl Indicator for UpsidePotential {
     type Input = f64;
     type Output = f64;
 
-    fn feed(&mut self, ret: Self::Input) {
-        self.input.push(ret);
-        if self.input.len() >= self.freq {
-            let value = self.input[self.input.len() - self.freq..]
-                .iter()
-                .fold(0.0, |acc, x| {
-                    acc + (x - self.mar).max(0.0) / self.input.len() as f64
-                });
-            self.values.push(Some(value));
-        } else {
-            self.values.push(None)
-        }
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.upper_partial_moment.feed(ret)?;
+        self.values.push(self.upper_partial_moment.last().copied());
+        Ok(())
     }
 
     fn last(&self) -> Option<&Self::Output> {
-        self.values.last().and_then(|v| v.as_ref())
+        self.values.last()
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
-        Box::new(self.values.iter().map(Option::as_ref))
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
     }
 }
 
 pub trait UpsidePotentialExt {
-    fn upside_potential(&self, freq: usize, mar: f64) -> Option<UpsidePotential>;
+    fn upside_potential(&self, freq: usize, mar: f64) -> Result<UpsidePotential, Error>;
 }
 
 impl<T> UpsidePotentialExt for T
 where
     T: ReturnExt,
 {
-    fn upside_potential(&self, freq: usize, mar: f64) -> Option<UpsidePotential> {
-        let mut indicator = UpsidePotential::new(freq, mar);
-        if let Some(ret) = self.ret() {
-            ret.iter().for_each(|&v| indicator.feed(v));
-            Some(indicator)
-        } else {
-            None
-        }
+    fn upside_potential(&self, freq: usize, mar: f64) -> Result<UpsidePotential, Error> {
+        feed_all(UpsidePotential::new(freq, mar)?, self.ret()?)
     }
 }
 
@@ -76,8 +97,20 @@ mod test {
     ];
     #[test]
     fn downside_potential() {
-        let mut indicator = UpsidePotential::new(10, 0.1 / 100.0);
-        XS.iter().for_each(|x| indicator.feed(*x));
+        let mut indicator = UpsidePotential::new(10, 0.1 / 100.0).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
         assert_approx_eq!(f64, 0.0194, *indicator.last().unwrap(), epsilon = 0.0000001);
     }
+    #[test]
+    fn rolling_matches_batch() {
+        let mar = 0.1 / 100.0;
+        let mut indicator = UpsidePotential::new(4, mar).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        for (i, value) in indicator.iter().enumerate().skip(3) {
+            let batch = XS[i - 3..=i]
+                .iter()
+                .fold(0.0, |acc, x| acc + (x - mar).max(0.0) / 4.0);
+            assert_approx_eq!(f64, batch, *value.unwrap(), epsilon = 0.0000001);
+        }
+    }
 }
@@ -0,0 +1,124 @@
+use crate::{
+    downside_risk::DownsideRisk,
+    feed_all,
+    history::{History, Values},
+    nan_policy::NanPolicy,
+    upside_potential::UpsidePotential,
+    Error, Indicator, ReturnExt,
+};
+
+#[derive(Debug)]
+pub struct UpsidePotentialRatio {
+    pub freq: usize,
+    pub mar: f64,
+    upside_potential: UpsidePotential,
+    downside_risk: DownsideRisk,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl UpsidePotentialRatio {
+    pub fn new(freq: usize, mar: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        Ok(Self {
+            freq,
+            mar,
+            upside_potential: UpsidePotential::new(freq, mar)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            downside_risk: DownsideRisk::new(freq, mar)?
+                .with_history(History::Last(1))
+                .with_nan_policy(NanPolicy::Propagate),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl Indicator for UpsidePotentialRatio {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.upside_potential.feed(ret)?;
+        self.downside_risk.feed(ret)?;
+        match (self.upside_potential.last(), self.downside_risk.last()) {
+            (Some(_), Some(&0.0)) => {
+                self.values.push(None);
+                return Err(Error::ZeroVariance);
+            }
+            (Some(upside), Some(downside_risk)) => self.values.push(Some(upside / downside_risk)),
+            _ => self.values.push(None),
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait UpsidePotentialRatioExt {
+    fn upside_potential_ratio(&self, freq: usize, mar: f64) -> Result<UpsidePotentialRatio, Error>;
+}
+
+impl<T> UpsidePotentialRatioExt for T
+where
+    T: ReturnExt,
+{
+    fn upside_potential_ratio(&self, freq: usize, mar: f64) -> Result<UpsidePotentialRatio, Error> {
+        feed_all(UpsidePotentialRatio::new(freq, mar)?, self.ret()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::UpsidePotentialRatio;
+    use crate::Indicator;
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn upside_potential_ratio() {
+        let mut indicator = UpsidePotentialRatio::new(10, 0.1 / 100.0).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        assert_approx_eq!(
+            f64,
+            0.0194 / 0.00570088,
+            *indicator.last().unwrap(),
+            epsilon = 0.0001
+        );
+    }
+}
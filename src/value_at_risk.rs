@@ -0,0 +1,330 @@
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::{
+    feed_all,
+    history::{History, Values},
+    method,
+    nan_policy::NanPolicy,
+    rolling::{RollingMoments, Window},
+    Error, Indicator, ReturnExt,
+};
+
+/// Return quantile at `1 - confidence` over the window, so a loss comes out
+/// negative.
+#[derive(Debug)]
+pub struct ValueAtRisk<T> {
+    pub method: T,
+    pub freq: usize,
+    pub confidence: f64,
+    z: f64,
+    input: Window,
+    moments: RollingMoments,
+    pub nan_policy: NanPolicy,
+    pub values: Values<f64>,
+}
+
+impl<T> ValueAtRisk<T> {
+    pub fn new(method: T, freq: usize, confidence: f64) -> Result<Self, Error> {
+        if freq == 0 {
+            return Err(Error::ZeroWindow);
+        }
+        if !(confidence > 0.0 && confidence < 1.0) {
+            return Err(Error::InvalidParameter);
+        }
+        Ok(Self {
+            method,
+            freq,
+            confidence,
+            z: standard_normal_quantile(1.0 - confidence),
+            input: Window::new(freq),
+            moments: RollingMoments::default(),
+            nan_policy: NanPolicy::default(),
+            values: Values::with_capacity(freq),
+        })
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.values.set_history(history);
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+pub(crate) fn standard_normal_quantile(p: f64) -> f64 {
+    Normal::new(0.0, 1.0).unwrap().inverse_cdf(p)
+}
+
+/// Sample quantile with linear interpolation between order statistics, the
+/// default (type 7) definition of R's `quantile`.
+pub(crate) fn quantile(xs: &mut [f64], p: f64) -> f64 {
+    xs.sort_by(f64::total_cmp);
+    let h = (xs.len() - 1) as f64 * p;
+    let lo = h.floor() as usize;
+    match xs.get(lo + 1) {
+        Some(hi) => xs[lo] + (h - lo as f64) * (hi - xs[lo]),
+        None => xs[lo],
+    }
+}
+
+/// Population skewness and excess kurtosis of `xs` around `mean`.
+pub(crate) fn skew_kurtosis<'a>(xs: impl Iterator<Item = &'a f64>, mean: f64) -> (f64, f64) {
+    let (mut n, mut m2, mut m3, mut m4) = (0.0, 0.0, 0.0, 0.0);
+    for x in xs {
+        let d = x - mean;
+        n += 1.0;
+        m2 += d * d;
+        m3 += d * d * d;
+        m4 += d * d * d * d;
+    }
+    let (m2, m3, m4) = (m2 / n, m3 / n, m4 / n);
+    (m3 / m2.powf(1.5), m4 / (m2 * m2) - 3.0)
+}
+
+/// Cornish-Fisher expansion of the standard normal quantile `z`.
+pub(crate) fn cornish_fisher(z: f64, skew: f64, kurtosis: f64) -> f64 {
+    z + (z.powi(2) - 1.0) * skew / 6.0 + (z.powi(3) - 3.0 * z) * kurtosis / 24.0
+        - (2.0 * z.powi(3) - 5.0 * z) * skew.powi(2) / 36.0
+}
+
+impl Indicator for ValueAtRisk<method::Historical> {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        self.input.push(ret);
+        if self.input.is_full() && !self.input.is_finite() {
+            self.values.push(Some(f64::NAN));
+        } else if self.input.is_full() {
+            let mut xs: Vec<f64> = self.input.iter().copied().collect();
+            self.values
+                .push(Some(quantile(&mut xs, 1.0 - self.confidence)));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+impl Indicator for ValueAtRisk<method::Gaussian> {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        if let Some(old) = self.input.push(ret) {
+            self.moments.remove(old);
+        }
+        self.moments.add(ret);
+        if self.input.is_full() {
+            let value = self.moments.mean() + self.z * self.moments.std_dev();
+            self.values.push(Some(value));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+impl Indicator for ValueAtRisk<method::CornishFisher> {
+    type Input = f64;
+    type Output = f64;
+
+    fn feed(&mut self, ret: Self::Input) -> Result<(), Error> {
+        let Some(ret) = self.nan_policy.apply(ret)? else {
+            self.values.push(None);
+            return Ok(());
+        };
+        if let Some(old) = self.input.push(ret) {
+            self.moments.remove(old);
+        }
+        self.moments.add(ret);
+        if self.input.is_full() && !self.input.is_finite() {
+            self.values.push(Some(f64::NAN));
+        } else if self.input.is_full() {
+            let mean = self.moments.mean();
+            let std_dev = self.moments.std_dev();
+            if std_dev == 0.0 {
+                self.values.push(None);
+                return Err(Error::ZeroVariance);
+            }
+            let (skew, kurtosis) = skew_kurtosis(self.input.iter(), mean);
+            let z = cornish_fisher(self.z, skew, kurtosis);
+            self.values.push(Some(mean + z * std_dev));
+        } else {
+            self.values.push(None);
+        }
+        Ok(())
+    }
+
+    fn last(&self) -> Option<&Self::Output> {
+        self.values.last()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Option<&Self::Output>> + '_> {
+        Box::new(self.values.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Output> {
+        self.values.get(index)
+    }
+}
+
+pub trait VarExt<T> {
+    fn value_at_risk(
+        &self,
+        method: T,
+        freq: usize,
+        confidence: f64,
+    ) -> Result<ValueAtRisk<T>, Error>;
+}
+
+impl<T> VarExt<method::Historical> for T
+where
+    T: ReturnExt,
+{
+    fn value_at_risk(
+        &self,
+        method: method::Historical,
+        freq: usize,
+        confidence: f64,
+    ) -> Result<ValueAtRisk<method::Historical>, Error> {
+        feed_all(ValueAtRisk::new(method, freq, confidence)?, self.ret()?)
+    }
+}
+
+impl<T> VarExt<method::Gaussian> for T
+where
+    T: ReturnExt,
+{
+    fn value_at_risk(
+        &self,
+        method: method::Gaussian,
+        freq: usize,
+        confidence: f64,
+    ) -> Result<ValueAtRisk<method::Gaussian>, Error> {
+        feed_all(ValueAtRisk::new(method, freq, confidence)?, self.ret()?)
+    }
+}
+
+impl<T> VarExt<method::CornishFisher> for T
+where
+    T: ReturnExt,
+{
+    fn value_at_risk(
+        &self,
+        method: method::CornishFisher,
+        freq: usize,
+        confidence: f64,
+    ) -> Result<ValueAtRisk<method::CornishFisher>, Error> {
+        feed_all(ValueAtRisk::new(method, freq, confidence)?, self.ret()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use float_cmp::assert_approx_eq;
+
+    use super::ValueAtRisk;
+    use crate::{method, Error, Indicator};
+
+    static XS: [f64; 10] = [
+        0.003, 0.026, 0.015, -0.009, 0.014, 0.024, 0.015, 0.066, -0.014, 0.039,
+    ];
+
+    #[test]
+    fn historical() {
+        let mut indicator = ValueAtRisk::new(method::Historical, 10, 0.95).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        assert_approx_eq!(f64, -0.01175, *indicator.last().unwrap(), epsilon = 1e-9);
+    }
+
+    #[test]
+    fn gaussian() {
+        let mut indicator = ValueAtRisk::new(method::Gaussian, 10, 0.95).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        assert_approx_eq!(
+            f64,
+            -0.020310751835047103,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn cornish_fisher() {
+        let mut indicator = ValueAtRisk::new(method::CornishFisher, 10, 0.95).unwrap();
+        XS.iter().for_each(|x| indicator.feed(*x).unwrap());
+        assert_approx_eq!(
+            f64,
+            -0.016049270240411445,
+            *indicator.last().unwrap(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn invalid_confidence() {
+        for confidence in [0.0, 1.0, 95.0, f64::NAN] {
+            assert_eq!(
+                Err(Error::InvalidParameter),
+                ValueAtRisk::new(method::Historical, 10, confidence).map(|_| ())
+            );
+        }
+    }
+
+    #[test]
+    fn rolls_into_flat_window() {
+        let mut indicator = ValueAtRisk::new(method::CornishFisher, 3, 0.95).unwrap();
+        for x in [0.037, -0.021, 0.013, 0.01, 0.01] {
+            indicator.feed(x).unwrap();
+        }
+        assert_eq!(Err(Error::ZeroVariance), indicator.feed(0.01));
+    }
+}